@@ -0,0 +1,38 @@
+#!/usr/bin/env python3
+"""Bot playing a random legal card, talking the engine's line-based bot protocol."""
+
+import random
+import sys
+
+LIGHT_COLORS = ["Red", "Blue", "Green", "Yellow"]
+DARK_COLORS = ["Pink", "Teal", "Orange", "Purple"]
+
+
+def main():
+    for line in sys.stdin:
+        command, *args = line.split()
+        if command == "uno":
+            answer = "ready"
+        elif command == "turn":
+            id, *fields = args
+            view = dict(field.split("=", 1) for field in fields)
+            card = random.choice(view["legal"].split(","))
+            # wild cards are written without color, so choose one of the side facing up
+            if ":" not in card:
+                is_dark = view["top"].split(":")[1] in DARK_COLORS
+                card += ":" + random.choice(DARK_COLORS if is_dark else LIGHT_COLORS)
+            answer = f"{id} play {card}"
+        elif command == "color":
+            id, *fields = args
+            view = dict(field.split("=", 1) for field in fields)
+            colors = DARK_COLORS if view["side"] == "dark" else LIGHT_COLORS
+            answer = f"{id} color {random.choice(colors)}"
+        elif command == "quit":
+            break
+        else:
+            continue
+        print(answer, flush=True)
+
+
+if __name__ == "__main__":
+    main()
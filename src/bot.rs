@@ -0,0 +1,437 @@
+use crate::env::{COLORS, SYMBOLS};
+use crate::greedy::most_held_color;
+use crate::{Card, Cards, Color, Direction, Play, PlayerView, Rules, SaveWildsStrategy, Side};
+use crate::{Strategy, Symbol};
+use std::cell::RefCell;
+use std::io::{self, BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const TIMEOUT: Duration = Duration::from_secs(1); // default time to answer a message
+
+/// Format `card` as protocol token.
+pub(crate) fn fmt_token(card: &Card) -> String {
+    let symbol = match card.symbol {
+        Symbol::Number(number) => number.to_string(),
+        symbol => symbol.to_string(),
+    };
+    match card.color {
+        Some(color) => format!("{}:{:?}", symbol, color),
+        None => symbol,
+    }
+}
+
+/// Format `cards` as comma-separated protocol tokens.
+pub(crate) fn fmt_tokens(cards: &[Card]) -> String {
+    match cards.is_empty() {
+        true => String::from("-"),
+        false => cards.iter().map(fmt_token).collect::<Vec<_>>().join(","),
+    }
+}
+
+/// Parse color from its name.
+pub(crate) fn parse_color(s: &str) -> Option<Color> {
+    COLORS.into_iter().find(|color| format!("{:?}", color) == s)
+}
+
+/// Parse card face from protocol token, returning its symbol and color if given.
+pub(crate) fn parse_token(s: &str) -> Option<(Symbol, Option<Color>)> {
+    let (symbol, color) = match s.split_once(':') {
+        Some((symbol, color)) => (symbol, Some(parse_color(color)?)),
+        None => (s, None),
+    };
+    let symbol = match symbol.parse::<u8>() {
+        Ok(number) => Symbol::Number(number),
+        Err(_) => SYMBOLS.into_iter().find(|x| x.to_string() == symbol)?,
+    };
+    Some((symbol, color))
+}
+
+/// Find card of `cards` with the face given by protocol `token`, with the color of wild
+/// cards set as given.
+pub(crate) fn find_card(cards: &[Card], token: &str) -> Option<Card> {
+    let (symbol, color) = parse_token(token)?;
+    let mut card = *cards
+        .iter()
+        .find(|x| x.symbol == symbol && (x.is_wild() || x.color == color))?;
+    if card.is_wild() {
+        card.color = color;
+    }
+    Some(card)
+}
+
+/// Find card of `legal_cards` played by protocol `token`, choosing the most held color
+/// of the hand in `view` for wild cards without color. Colors of the other side are
+/// illegal.
+pub(crate) fn find_play(legal_cards: &[Card], token: &str, view: &PlayerView) -> Option<Card> {
+    let mut card = find_card(legal_cards, token)?;
+    match card.color {
+        Some(color) if !card.side.colors().contains(&color) => return None,
+        Some(_) => (),
+        None => card.color = Some(most_held_color(&view.hand, card.side)),
+    }
+    Some(card)
+}
+
+/// Format `values` as comma-separated list.
+fn fmt_list(values: &[usize]) -> String {
+    values
+        .iter()
+        .map(|x| x.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Format `turn` message for `view` and `legal_cards`.
+fn fmt_turn(id: u64, view: &PlayerView, legal_cards: &[Card]) -> String {
+    let direction = match view.direction {
+        Direction::Forward => "forward",
+        Direction::Reverse => "reverse",
+    };
+    let teams = match &view.teams {
+        Some(teams) => fmt_list(teams),
+        None => String::from("-"),
+    };
+    let n_other_cards = view.hand_sizes.iter().sum::<usize>() - view.hand.len();
+    let n_deck = view.unknown_cards.len() - n_other_cards;
+    format!(
+        "turn {} player={} next={} direction={} pending_draw={} top={} hand={} hand_sizes={} teams={} deck={} pile={} legal={}",
+        id,
+        view.index,
+        view.next,
+        direction,
+        view.pending_draw,
+        fmt_token(&view.top_card),
+        fmt_tokens(&view.hand),
+        fmt_list(&view.hand_sizes),
+        teams,
+        n_deck,
+        fmt_tokens(&view.pile),
+        fmt_tokens(legal_cards),
+    )
+}
+
+/// Running bot process with a thread forwarding the lines it writes.
+struct BotProcess {
+    child: Child,
+    stdin: ChildStdin,
+    lines: Receiver<String>,
+    next_id: u64,
+}
+
+impl BotProcess {
+    /// Write `message` to the bot.
+    fn send(&mut self, message: &str) -> io::Result<()> {
+        writeln!(self.stdin, "{}", message)?;
+        self.stdin.flush()
+    }
+
+    /// Receive next line other than `info` lines within `timeout`.
+    fn receive(&mut self, timeout: Duration) -> Result<String, RecvTimeoutError> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let line = self.lines.recv_timeout(remaining)?;
+            if !line.starts_with("info") {
+                return Ok(line);
+            }
+        }
+    }
+
+    /// Send message created by `message` from a new id and return the rest of the answer
+    /// with that id, or `None` if the bot does not answer within `timeout`.
+    fn request(
+        &mut self,
+        message: impl FnOnce(u64) -> String,
+        timeout: Duration,
+    ) -> Option<String> {
+        let id = self.next_id;
+        self.next_id += 1;
+        if let Err(error) = self.send(&message(id)) {
+            log!("Bot: cannot send message: {}", error);
+            return None;
+        }
+        let deadline = Instant::now() + timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let line = match self.receive(remaining) {
+                Ok(line) => line,
+                Err(RecvTimeoutError::Timeout) => {
+                    log!("Bot: no answer within {:?}", timeout);
+                    return None;
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    log!("Bot: process exited");
+                    return None;
+                }
+            };
+
+            // ignore late answers to earlier messages
+            match line.split_once(' ') {
+                Some((answer_id, rest)) if answer_id == id.to_string() => {
+                    return Some(rest.to_string())
+                }
+                _ => log!("Bot: ignored answer: {}", line),
+            }
+        }
+    }
+}
+
+impl Drop for BotProcess {
+    fn drop(&mut self) {
+        // ask the bot to quit, killing it if it does not exit in time
+        let _ = self.send("quit");
+        let deadline = Instant::now() + Duration::from_millis(100);
+        while Instant::now() < deadline {
+            if let Ok(Some(_)) = self.child.try_wait() {
+                return;
+            }
+            thread::sleep(Duration::from_millis(5));
+        }
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Strategy delegating decisions to a bot running as external process, talking a
+/// line-based protocol similar to UCI for chess. Other decisions, like challenging wild
+/// draw 4s, follow the defaults of [`Strategy`].
+///
+/// The engine writes one message per line to the bot's stdin and reads answers from its
+/// stdout. Cards are written as `<symbol>` or `<symbol>:<color>`, e.g. `7:Red`,
+/// `Skip:Blue`, `WildDraw4` or `Wild:Green` for a wild card with chosen color, and lists
+/// of cards are separated by commas, with `-` for an empty list.
+///
+/// Engine to bot:
+/// - `uno`: handshake after starting the bot, answered with `ready`.
+/// - `rules <json>`: rules of the games, as single-line JSON.
+/// - `turn <id> player=<i> next=<i> direction=<forward|reverse> pending_draw=<n>
+///   top=<card> hand=<cards> hand_sizes=<n,..> teams=<t,..|-> deck=<n> pile=<cards>
+///   legal=<cards>`: select a card, answered with `<id> play <card>` for one of the
+///   legal cards, choosing the color of wild cards, or `<id> draw`.
+/// - `color <id> hand=<cards> side=<light|dark>`: select the color of a wild card,
+///   answered with `<id> color <color>`.
+/// - `quit`: the bot should exit.
+///
+/// Lines starting with `info` are ignored, as are answers with an outdated id, e.g.
+/// answers arriving after the timeout. Late, malformed or illegal answers fall back to
+/// saving wild cards for that decision.
+pub struct ProcessStrategy {
+    process: RefCell<BotProcess>,
+    timeout: Duration,
+}
+
+impl ProcessStrategy {
+    /// Spawn bot by running `program` with `args` for games played with `rules`, and
+    /// wait for the handshake within `timeout`.
+    pub fn spawn(
+        program: &str,
+        args: &[&str],
+        rules: &Rules,
+        timeout: Duration,
+    ) -> io::Result<Self> {
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let stdin = child.stdin.take().expect("stdin is piped");
+        let stdout = child.stdout.take().expect("stdout is piped");
+
+        // forward lines written by the bot, so that reading can time out
+        let (sender, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if sender.send(line.trim().to_string()).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut process = BotProcess {
+            child,
+            stdin,
+            lines,
+            next_id: 0,
+        };
+        process.send("uno")?;
+        match process.receive(timeout) {
+            Ok(line) if line == "ready" => {}
+            Ok(line) => {
+                let message = format!("unexpected handshake: {}", line);
+                return Err(io::Error::new(io::ErrorKind::InvalidData, message));
+            }
+            Err(_) => {
+                let message = "no handshake from bot";
+                return Err(io::Error::new(io::ErrorKind::TimedOut, message));
+            }
+        }
+        let rules = serde_json::to_string(rules).expect("rules are serializable");
+        process.send(&format!("rules {}", rules))?;
+
+        Ok(Self {
+            process: RefCell::new(process),
+            timeout,
+        })
+    }
+
+    /// Spawn bot by running shell-like `command`, splitting it on whitespace, with the
+    /// default timeout of 1 second, see [`ProcessStrategy::spawn`].
+    pub fn from_command(command: &str, rules: &Rules) -> io::Result<Self> {
+        let mut words = command.split_whitespace();
+        let program = words
+            .next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "empty bot command"))?;
+        let args: Vec<&str> = words.collect();
+        Self::spawn(program, &args, rules, TIMEOUT)
+    }
+
+    /// Send message created by `message` and return the answer, if any.
+    fn request(&self, message: impl FnOnce(u64) -> String) -> Option<String> {
+        self.process.borrow_mut().request(message, self.timeout)
+    }
+}
+
+impl Strategy for ProcessStrategy {
+    /// Select card without view, saving wild cards, as the protocol sends the view.
+    fn select_card(&self, legal_cards: Cards) -> Play {
+        SaveWildsStrategy {}.select_card(legal_cards)
+    }
+
+    fn select_card_with_view(&self, legal_cards: Cards, view: &PlayerView) -> Play {
+        let answer = self.request(|id| fmt_turn(id, view, &legal_cards));
+        let play = match answer
+            .as_deref()
+            .map(|x| x.split_once(' ').unwrap_or((x, "")))
+        {
+            Some(("draw", "")) => return None,
+            Some(("play", token)) => find_play(&legal_cards, token, view),
+            Some(_) => None,
+            None => return SaveWildsStrategy {}.select_card_with_view(legal_cards, view),
+        };
+        play.or_else(|| {
+            log!("Bot: illegal answer: {}", answer.unwrap_or_default());
+            SaveWildsStrategy {}.select_card_with_view(legal_cards, view)
+        })
+    }
+
+    fn select_color(&self, hand: &Cards, side: Side) -> Color {
+        let side_name = match side {
+            Side::Light => "light",
+            Side::Dark => "dark",
+        };
+        let answer =
+            self.request(|id| format!("color {} hand={} side={}", id, fmt_tokens(hand), side_name));
+        let color = answer
+            .as_deref()
+            .and_then(|x| x.strip_prefix("color "))
+            .and_then(parse_color)
+            .filter(|x| side.colors().contains(x));
+        color.unwrap_or_else(|| {
+            log!("Bot: illegal answer: {}", answer.unwrap_or_default());
+            most_held_color(hand, side)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*; // bring private functions into scope
+    use crate::Game;
+    use rstest::rstest;
+
+    const TIMEOUT: Duration = Duration::from_millis(500);
+
+    // bot playing the first legal card
+    const FIRST_CARD_BOT: &str = r#"
+        while read command id rest; do
+            case $command in
+                uno) echo ready ;;
+                turn) card=${rest##*legal=}; echo "$id play ${card%%,*}" ;;
+                color) echo "$id color Blue" ;;
+                quit) exit ;;
+            esac
+        done
+    "#;
+
+    fn spawn(script: &str) -> io::Result<ProcessStrategy> {
+        ProcessStrategy::spawn("sh", &["-c", script], &Rules::official(), TIMEOUT)
+    }
+
+    #[rstest]
+    #[case(Card::new(Symbol::Number(7), Some(Color::Red)), "7:Red")]
+    #[case(Card::new(Symbol::Skip, Some(Color::Blue)), "Skip:Blue")]
+    #[case(Card::new(Symbol::WildDraw4, None), "WildDraw4")]
+    #[case(Card::new(Symbol::Wild, Some(Color::Green)), "Wild:Green")]
+    fn test_fmt_parse_token(#[case] card: Card, #[case] expected: &str) {
+        assert_eq!(fmt_token(&card), expected);
+        assert_eq!(parse_token(expected), Some((card.symbol, card.color)));
+    }
+
+    #[test]
+    fn test_find_card() {
+        let cards = vec![
+            Card::new(Symbol::Number(7), Some(Color::Red)),
+            Card::new(Symbol::Wild, None),
+        ];
+        assert_eq!(find_card(&cards, "7:Red"), Some(cards[0]));
+        assert_eq!(find_card(&cards, "7:Blue"), None);
+        assert_eq!(
+            find_card(&cards, "Wild:Yellow"),
+            Some(Card::new(Symbol::Wild, Some(Color::Yellow)))
+        );
+        assert_eq!(find_card(&cards, "Purple:7"), None);
+    }
+
+    #[test]
+    fn test_process_strategy_plays_game() {
+        let mut game = Game::new(2, 0, Rules::official());
+        game.set_strategy(0, Box::new(spawn(FIRST_CARD_BOT).unwrap()));
+        let result = game.play();
+        assert!(result.winner < 2);
+    }
+
+    #[test]
+    fn test_process_strategy_plays_legal_card() {
+        let strategy = spawn(FIRST_CARD_BOT).unwrap();
+        let mut game = Game::with_seed(2, 0, Rules::official(), 42);
+        game.step();
+        let view = game.view(0);
+        let legal_cards = vec![view.hand[0]];
+        let play = strategy.select_card_with_view(legal_cards.clone(), &view);
+        let card = play.unwrap();
+        assert!(card.is_equal_ignore_wild_color(&legal_cards[0]));
+        assert!(card.color.is_some());
+        assert_eq!(strategy.select_color(&view.hand, Side::Light), Color::Blue);
+    }
+
+    #[rstest]
+    #[case::silent("while read line; do [ \"$line\" = uno ] && echo ready; done")]
+    #[case::illegal(
+        "while read c id rest; do [ $c = uno ] && echo ready || echo \"$id play 99:Red\"; done"
+    )]
+    #[case::late("while read c id rest; do [ $c = uno ] && echo ready || { sleep 1; echo \"$id draw\"; }; done")]
+    fn test_process_strategy_falls_back(#[case] script: &str) {
+        let strategy = spawn(script).unwrap();
+        let mut game = Game::with_seed(2, 0, Rules::official(), 42);
+        game.step();
+        let view = game.view(0);
+        let legal_cards = view.hand.clone();
+        let start = Instant::now();
+        let play = strategy.select_card_with_view(legal_cards.clone(), &view);
+        assert!(start.elapsed() < 2 * TIMEOUT);
+        let expected = SaveWildsStrategy {}.select_card_with_view(legal_cards, &view);
+        assert_eq!(play, expected);
+    }
+
+    #[test]
+    fn test_process_strategy_without_handshake() {
+        let result = spawn("read line");
+        assert_eq!(
+            result.err().map(|x| x.kind()),
+            Some(io::ErrorKind::TimedOut)
+        );
+    }
+}
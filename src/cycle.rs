@@ -1,60 +1,115 @@
+use std::cmp::Ordering;
+
 type Turn = u32;
 
+/// Direction in which a cycle moves through its values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Forward,
+    Reverse,
+}
+
+/// Turn order cycling over values, e.g. player indexes, in either direction.
 #[derive(Debug, Clone)]
-pub struct Cycle {
-    values: Vec<usize>,
+pub struct Cycle<T = usize> {
+    values: Vec<T>,
     current: usize,
     is_reversed: bool,
     turn: Turn,
 }
 
-impl Iterator for Cycle {
-    type Item = usize;
+impl<T: Clone> Iterator for Cycle<T> {
+    type Item = T;
 
     fn next(&mut self) -> Option<Self::Item> {
         // update current, depending on direction
-        let c = self.current as isize;
-        let next = match self.is_reversed {
-            true => c - 1,
-            false => c + 1,
-        };
-        let n_values = self.values.len() as isize;
-        let current = next.rem_euclid(n_values); // calculate the least non-negative remainder
-        assert!(current >= 0);
-        self.current = current as usize;
-
-        // select item
-        let item = *self.values.get(self.current).expect("out-of-bounds index");
+        self.current = self.position_after(self.is_reversed);
 
         // update turn
         self.turn += 1;
 
         // return item
-        Some(item)
+        self.values.get(self.current).cloned()
     }
 }
 
-impl Cycle {
-    /// Create generator that cycles over values from range 0..`n`.
-    pub fn new(n_values: usize) -> Self {
-        let values = (0..n_values).collect();
+// iterating from the back moves against the direction of the cycle
+impl<T: Clone> DoubleEndedIterator for Cycle<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.current = self.position_after(!self.is_reversed);
+        self.turn += 1;
+        self.values.get(self.current).cloned()
+    }
+}
+
+impl Cycle<usize> {
+    /// Create generator that cycles over values from range 0..`n`, starting at `first`.
+    pub fn new(n_values: usize, first: usize) -> Self {
+        Self::from_values((0..n_values).collect(), first)
+    }
+}
+
+impl<T: Clone> Cycle<T> {
+    /// Create generator that cycles over `values`, starting at the value at position
+    /// `first`.
+    pub fn from_values(values: Vec<T>, first: usize) -> Self {
+        let n_values = values.len();
+        assert!(first < n_values);
         // initialized with forward direction and start position so that next value will
         // be the first value
         Self {
             values,
-            current: n_values - 1,
+            current: (first + n_values - 1) % n_values,
             is_reversed: false,
             turn: 0,
         }
     }
 
+    /// Get position following the current position, moving backwards if `reversed`.
+    fn position_after(&self, reversed: bool) -> usize {
+        let c = self.current as isize;
+        let next = match reversed {
+            true => c - 1,
+            false => c + 1,
+        };
+        let n_values = self.values.len() as isize;
+        next.rem_euclid(n_values) as usize // calculate the least non-negative remainder
+    }
+
+    /// Get next value without advancing the cycle.
+    pub fn peek_next(&self) -> T {
+        self.values[self.position_after(self.is_reversed)].clone()
+    }
+
+    /// Get current value, i.e. the value returned last, or `None` before the first turn.
+    pub fn current(&self) -> Option<T> {
+        match self.turn {
+            0 => None,
+            _ => Some(self.values[self.current].clone()),
+        }
+    }
+
+    /// Advance cycle by `n` values without returning them, each counting as a turn.
+    ///
+    /// This is the `skip(n)` of the turn order, named differently because method calls
+    /// resolve to [`Iterator::skip`] first, which would consume the cycle instead.
+    pub fn skip_turns(&mut self, n: usize) {
+        for _ in 0..n {
+            self.next();
+        }
+    }
+
     /// Reverse cycle.
     pub fn reverse(&mut self) {
         // if we reverse in the first turn, we need to change the starting position
-        // to the first value, so that the next value will be the last value
+        // to the first value, so that the next value will be the one before the first value,
+        // and back when reversing again
         if self.turn == 0 {
-            assert!(!self.is_reversed);
-            self.current = 0;
+            let n_values = self.values.len();
+            self.current = match self.is_reversed {
+                true => (self.current + n_values - 1) % n_values,
+                false => (self.current + 1) % n_values,
+            };
         }
 
         // reverse direction, if reversed already, reverse back
@@ -64,10 +119,68 @@ impl Cycle {
         }
     }
 
+    /// Get direction of cycle.
+    pub fn direction(&self) -> Direction {
+        match self.is_reversed {
+            true => Direction::Reverse,
+            false => Direction::Forward,
+        }
+    }
+
+    /// Check if cycle is reversed.
+    pub fn is_reversed(&self) -> bool {
+        self.is_reversed
+    }
+
     /// Get turn number.
     pub fn turn(&self) -> Turn {
         self.turn
     }
+
+    /// Get number of values in cycle.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Check if cycle has no values, which can only happen for an empty cycle.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+impl<T: Clone + PartialEq> Cycle<T> {
+    /// Get position of `value`.
+    fn position_of(&self, value: &T) -> usize {
+        self.values
+            .iter()
+            .position(|x| x == value)
+            .expect("value not in cycle")
+    }
+
+    /// Continue cycle from `value`, so that the next value follows `value`.
+    pub fn jump_to(&mut self, value: T) {
+        self.current = self.position_of(&value);
+    }
+
+    /// Remove `value` from cycle, keeping the direction and the position, so that the
+    /// next value is the one that would have followed `value` if it is the current one.
+    pub fn remove(&mut self, value: T) {
+        let position = self.position_of(&value);
+        assert!(self.values.len() > 1, "cannot remove last value");
+        self.values.remove(position);
+        let n_values = self.values.len();
+        self.current = match position.cmp(&self.current) {
+            Ordering::Less => self.current - 1,
+            Ordering::Greater => self.current,
+            // the next value moved into the removed position, step back so that it
+            // follows in forward direction, while the previous value still follows in
+            // reverse direction
+            Ordering::Equal => match self.is_reversed {
+                true => position % n_values,
+                false => (position + n_values - 1) % n_values,
+            },
+        };
+    }
 }
 
 #[cfg(test)]
@@ -76,7 +189,7 @@ mod tests {
 
     #[test]
     fn test_cycle_iter_values_next_reverse_next_3() {
-        let mut cycle = Cycle::new(3);
+        let mut cycle = Cycle::new(3, 0);
         assert_eq!(cycle.next().unwrap(), 0);
         assert_eq!(cycle.next().unwrap(), 1);
         assert_eq!(cycle.next().unwrap(), 2);
@@ -93,7 +206,7 @@ mod tests {
 
     #[test]
     fn test_cycle_iter_values_next_reverse_next_5() {
-        let mut cycle = Cycle::new(5);
+        let mut cycle = Cycle::new(5, 0);
         assert_eq!(cycle.next().unwrap(), 0);
         assert_eq!(cycle.next().unwrap(), 1);
         assert_eq!(cycle.next().unwrap(), 2);
@@ -109,7 +222,7 @@ mod tests {
 
     #[test]
     fn test_cycle_iter_values_reverse_next() {
-        let mut cycle = Cycle::new(3);
+        let mut cycle = Cycle::new(3, 0);
         cycle.reverse();
         assert_eq!(cycle.next().unwrap(), 2);
         assert_eq!(cycle.next().unwrap(), 1);
@@ -118,7 +231,7 @@ mod tests {
 
     #[test]
     fn test_cycle_iter_values_next_reverse_reverse_next() {
-        let mut cycle = Cycle::new(3);
+        let mut cycle = Cycle::new(3, 0);
         assert_eq!(cycle.next().unwrap(), 0);
         assert_eq!(cycle.next().unwrap(), 1);
         assert_eq!(cycle.next().unwrap(), 2);
@@ -129,9 +242,150 @@ mod tests {
         assert_eq!(cycle.next().unwrap(), 2);
     }
 
+    #[test]
+    fn test_cycle_iter_values_reverse_reverse_next() {
+        let mut cycle = Cycle::new(4, 2);
+        cycle.reverse();
+        cycle.reverse();
+        assert_eq!(cycle.direction(), Direction::Forward);
+        assert_eq!(cycle.next().unwrap(), 2);
+        assert_eq!(cycle.next().unwrap(), 3);
+        cycle.reverse();
+        cycle.reverse();
+        cycle.reverse();
+        assert_eq!(cycle.next().unwrap(), 2);
+    }
+
+    #[test]
+    fn test_cycle_iter_values_first_next_reverse_next() {
+        let mut cycle = Cycle::new(4, 2);
+        assert_eq!(cycle.next().unwrap(), 2);
+        assert_eq!(cycle.next().unwrap(), 3);
+        assert_eq!(cycle.next().unwrap(), 0);
+        cycle.reverse();
+        assert_eq!(cycle.next().unwrap(), 3);
+    }
+
+    #[test]
+    fn test_cycle_iter_values_first_reverse_next() {
+        let mut cycle = Cycle::new(4, 2);
+        cycle.reverse();
+        assert_eq!(cycle.next().unwrap(), 1);
+        assert_eq!(cycle.next().unwrap(), 0);
+        assert_eq!(cycle.next().unwrap(), 3);
+    }
+
+    #[test]
+    fn test_cycle_iter_values_next_jump_to_next() {
+        let mut cycle = Cycle::new(4, 0);
+        assert_eq!(cycle.next().unwrap(), 0);
+        cycle.jump_to(2);
+        assert_eq!(cycle.next().unwrap(), 3);
+        cycle.reverse();
+        cycle.jump_to(1);
+        assert_eq!(cycle.next().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_cycle_remove_other_values() {
+        let mut cycle = Cycle::new(5, 0);
+        assert_eq!(cycle.next().unwrap(), 0);
+        assert_eq!(cycle.next().unwrap(), 1);
+        cycle.remove(0);
+        cycle.remove(3);
+        assert_eq!(cycle.len(), 3);
+        assert_eq!(cycle.next().unwrap(), 2);
+        assert_eq!(cycle.next().unwrap(), 4);
+        assert_eq!(cycle.next().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_cycle_remove_current_value() {
+        let mut cycle = Cycle::new(4, 0);
+        assert_eq!(cycle.next().unwrap(), 0);
+        assert_eq!(cycle.next().unwrap(), 1);
+        cycle.remove(1);
+        assert_eq!(cycle.next().unwrap(), 2);
+        assert_eq!(cycle.next().unwrap(), 3);
+        cycle.remove(3);
+        assert_eq!(cycle.next().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_cycle_remove_current_value_reversed() {
+        let mut cycle = Cycle::new(4, 0);
+        cycle.reverse();
+        assert_eq!(cycle.next().unwrap(), 3);
+        cycle.remove(3);
+        assert_eq!(cycle.next().unwrap(), 2);
+        assert_eq!(cycle.next().unwrap(), 1);
+        assert_eq!(cycle.next().unwrap(), 0);
+        cycle.remove(0);
+        assert_eq!(cycle.next().unwrap(), 2);
+    }
+
+    #[test]
+    fn test_cycle_remove_before_first_turn() {
+        let mut cycle = Cycle::new(3, 0);
+        cycle.remove(0);
+        assert_eq!(cycle.next().unwrap(), 1);
+        assert_eq!(cycle.next().unwrap(), 2);
+    }
+
+    #[test]
+    fn test_cycle_peek_next_does_not_advance() {
+        let mut cycle = Cycle::new(3, 1);
+        assert_eq!(cycle.peek_next(), 1);
+        assert_eq!(cycle.current(), None);
+        assert_eq!(cycle.next().unwrap(), 1);
+        assert_eq!(cycle.current(), Some(1));
+        cycle.reverse();
+        assert_eq!(cycle.peek_next(), 0);
+        assert_eq!(cycle.next().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_cycle_skip_turns() {
+        let mut cycle = Cycle::new(4, 0);
+        cycle.skip_turns(2);
+        assert_eq!(cycle.current(), Some(1));
+        assert_eq!(cycle.turn(), 2);
+        assert_eq!(cycle.next().unwrap(), 2);
+    }
+
+    #[test]
+    fn test_cycle_direction() {
+        let mut cycle = Cycle::new(3, 0);
+        assert_eq!(cycle.direction(), Direction::Forward);
+        cycle.reverse();
+        assert_eq!(cycle.direction(), Direction::Reverse);
+    }
+
+    #[test]
+    fn test_cycle_next_back() {
+        let mut cycle = Cycle::new(3, 0);
+        assert_eq!(cycle.next().unwrap(), 0);
+        assert_eq!(cycle.next().unwrap(), 1);
+        assert_eq!(cycle.next_back().unwrap(), 0);
+        assert_eq!(cycle.next_back().unwrap(), 2);
+        cycle.reverse();
+        assert_eq!(cycle.next_back().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_cycle_generic_values() {
+        let mut cycle = Cycle::from_values(vec!["a", "b", "c"], 2);
+        assert_eq!(cycle.next().unwrap(), "c");
+        assert_eq!(cycle.next().unwrap(), "a");
+        cycle.jump_to("b");
+        assert_eq!(cycle.next().unwrap(), "c");
+        cycle.remove("a");
+        assert_eq!(cycle.next().unwrap(), "b");
+    }
+
     #[test]
     fn test_cycle_iter_values_next() {
-        let mut cycle = Cycle::new(3);
+        let mut cycle = Cycle::new(3, 0);
         assert_eq!(cycle.next().unwrap(), 0);
         assert_eq!(cycle.next().unwrap(), 1);
         assert_eq!(cycle.next().unwrap(), 2);
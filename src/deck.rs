@@ -0,0 +1,354 @@
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+const N_COLORS: usize = 4; // number of colors in standard deck
+
+/// Composition of the deck, built from the standard deck by overriding the number of
+/// copies of single cards.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(remote = "Self", default, deny_unknown_fields)]
+pub struct DeckSpec {
+    pub(crate) n_decks: usize,
+    pub(crate) n_colors: usize,
+    pub(crate) numbers: [usize; 10], // copies per color, indexed by number
+    pub(crate) skips: usize,         // copies per color
+    pub(crate) reverses: usize,      // copies per color
+    pub(crate) draw_2s: usize,       // copies per color
+    pub(crate) draw_1s: usize,       // copies per color
+    pub(crate) flips: usize,         // copies per color
+    pub(crate) skip_everyones: usize, // copies per color
+    pub(crate) discard_alls: usize,  // copies per color
+    pub(crate) wilds: usize,
+    pub(crate) wild_draw_4s: usize,
+    pub(crate) wild_draw_2s: usize,
+    pub(crate) wild_draw_6s: usize,
+    pub(crate) wild_draw_10s: usize,
+    pub(crate) wild_reverse_draw_4s: usize,
+    pub(crate) wild_color_roulettes: usize,
+    pub(crate) double_sided: bool, // whether cards have a dark side mirroring the light side
+}
+
+// derived implementations are generated as inherent functions by `remote = "Self"`, so
+// that deserialized specs can be validated like those built with the builders
+impl Serialize for DeckSpec {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Self::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for DeckSpec {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let spec = Self::deserialize(deserializer)?;
+        spec.validate().map_err(D::Error::custom)?;
+        Ok(spec)
+    }
+}
+
+impl Default for DeckSpec {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+impl DeckSpec {
+    /// Standard deck with 108 cards.
+    pub fn standard() -> Self {
+        Self {
+            n_decks: 1,
+            n_colors: N_COLORS,
+            numbers: [1, 2, 2, 2, 2, 2, 2, 2, 2, 2],
+            skips: 2,
+            reverses: 2,
+            draw_2s: 2,
+            draw_1s: 0,
+            flips: 0,
+            skip_everyones: 0,
+            discard_alls: 0,
+            wilds: 4,
+            wild_draw_4s: 4,
+            wild_draw_2s: 0,
+            wild_draw_6s: 0,
+            wild_draw_10s: 0,
+            wild_reverse_draw_4s: 0,
+            wild_color_roulettes: 0,
+            double_sided: false,
+        }
+    }
+
+    /// Uno Flip deck with 112 double-sided cards, where the dark side mirrors the light
+    /// side with skip everyone, draw 5 and wild draw color cards.
+    pub fn flip() -> Self {
+        Self {
+            n_decks: 1,
+            n_colors: N_COLORS,
+            numbers: [0, 2, 2, 2, 2, 2, 2, 2, 2, 2],
+            skips: 2,
+            reverses: 2,
+            draw_2s: 0,
+            draw_1s: 2,
+            flips: 2,
+            skip_everyones: 0,
+            discard_alls: 0,
+            wilds: 4,
+            wild_draw_4s: 0,
+            wild_draw_2s: 4,
+            wild_draw_6s: 0,
+            wild_draw_10s: 0,
+            wild_reverse_draw_4s: 0,
+            wild_color_roulettes: 0,
+            double_sided: true,
+        }
+    }
+
+    /// "Show 'em No Mercy" deck with 156 cards, replacing plain wild cards with wild
+    /// draw 6, wild draw 10, wild reverse draw 4 and color roulette cards.
+    pub fn no_mercy() -> Self {
+        Self {
+            n_decks: 1,
+            n_colors: N_COLORS,
+            numbers: [2; 10],
+            skips: 3,
+            reverses: 3,
+            draw_2s: 3,
+            draw_1s: 0,
+            flips: 0,
+            skip_everyones: 2,
+            discard_alls: 2,
+            wilds: 0,
+            wild_draw_4s: 0,
+            wild_draw_2s: 0,
+            wild_draw_6s: 4,
+            wild_draw_10s: 4,
+            wild_reverse_draw_4s: 8,
+            wild_color_roulettes: 8,
+            double_sided: false,
+        }
+    }
+
+    /// Check that the deck consists of at least one deck with between 1 and 4 colors and
+    /// holds colored cards other than wild cards, which can be flipped as first card.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.n_decks == 0 {
+            return Err(String::from("deck must consist of at least one deck"));
+        }
+        if !(1..=N_COLORS).contains(&self.n_colors) {
+            return Err(format!(
+                "number of colors must be between 1 and {}",
+                N_COLORS
+            ));
+        }
+        if self.n_color_cards() == 0 {
+            return Err(String::from(
+                "deck must hold number or action cards besides wild cards",
+            ));
+        }
+        Ok(())
+    }
+
+    // panic if the spec is invalid, see `validate`
+    fn checked(self) -> Self {
+        if let Err(error) = self.validate() {
+            panic!("{}", error);
+        }
+        self
+    }
+
+    /// Combine `n_decks` decks with the same composition, e.g. for large tables.
+    pub fn n_decks(mut self, n_decks: usize) -> Self {
+        self.n_decks = n_decks;
+        self.checked()
+    }
+
+    /// Use the first `n_colors` colors.
+    pub fn n_colors(mut self, n_colors: usize) -> Self {
+        self.n_colors = n_colors;
+        self.checked()
+    }
+
+    /// Set `copies` per color of number card `number`.
+    pub fn number(mut self, number: u8, copies: usize) -> Self {
+        assert!(number <= 9, "number must be between 0 and 9");
+        self.numbers[number as usize] = copies;
+        self
+    }
+
+    /// Set `copies` per color of skip cards.
+    pub fn skips(mut self, copies: usize) -> Self {
+        self.skips = copies;
+        self
+    }
+
+    /// Set `copies` per color of reverse cards.
+    pub fn reverses(mut self, copies: usize) -> Self {
+        self.reverses = copies;
+        self
+    }
+
+    /// Set `copies` per color of draw 2 cards.
+    pub fn draw_2s(mut self, copies: usize) -> Self {
+        self.draw_2s = copies;
+        self
+    }
+
+    /// Set `copies` per color of draw 1 cards.
+    pub fn draw_1s(mut self, copies: usize) -> Self {
+        self.draw_1s = copies;
+        self
+    }
+
+    /// Set `copies` per color of flip cards.
+    pub fn flips(mut self, copies: usize) -> Self {
+        self.flips = copies;
+        self
+    }
+
+    /// Set `copies` per color of skip everyone cards.
+    pub fn skip_everyones(mut self, copies: usize) -> Self {
+        self.skip_everyones = copies;
+        self
+    }
+
+    /// Set `copies` per color of discard all cards.
+    pub fn discard_alls(mut self, copies: usize) -> Self {
+        self.discard_alls = copies;
+        self
+    }
+
+    /// Set `copies` of wild cards.
+    pub fn wilds(mut self, copies: usize) -> Self {
+        self.wilds = copies;
+        self
+    }
+
+    /// Set `copies` of wild draw 4 cards.
+    pub fn wild_draw_4s(mut self, copies: usize) -> Self {
+        self.wild_draw_4s = copies;
+        self
+    }
+
+    /// Set `copies` of wild draw 2 cards.
+    pub fn wild_draw_2s(mut self, copies: usize) -> Self {
+        self.wild_draw_2s = copies;
+        self
+    }
+
+    /// Set `copies` of wild draw 6 cards.
+    pub fn wild_draw_6s(mut self, copies: usize) -> Self {
+        self.wild_draw_6s = copies;
+        self
+    }
+
+    /// Set `copies` of wild draw 10 cards.
+    pub fn wild_draw_10s(mut self, copies: usize) -> Self {
+        self.wild_draw_10s = copies;
+        self
+    }
+
+    /// Set `copies` of wild reverse draw 4 cards.
+    pub fn wild_reverse_draw_4s(mut self, copies: usize) -> Self {
+        self.wild_reverse_draw_4s = copies;
+        self
+    }
+
+    /// Set `copies` of wild color roulette cards.
+    pub fn wild_color_roulettes(mut self, copies: usize) -> Self {
+        self.wild_color_roulettes = copies;
+        self
+    }
+
+    /// Make cards double-sided, adding a dark side mirroring the light side.
+    pub fn double_sided(mut self, double_sided: bool) -> Self {
+        self.double_sided = double_sided;
+        self
+    }
+
+    /// Get number of cards in the deck.
+    pub fn n_cards(&self) -> usize {
+        let n_wild_cards = self.wilds
+            + self.wild_draw_4s
+            + self.wild_draw_2s
+            + self.wild_draw_6s
+            + self.wild_draw_10s
+            + self.wild_reverse_draw_4s
+            + self.wild_color_roulettes;
+        self.n_color_cards() + self.n_decks * n_wild_cards
+    }
+
+    /// Get number of colored cards other than wild cards.
+    pub(crate) fn n_color_cards(&self) -> usize {
+        let n_numbers: usize = self.numbers.iter().sum();
+        let n_actions = self.skips
+            + self.reverses
+            + self.draw_2s
+            + self.draw_1s
+            + self.flips
+            + self.skip_everyones
+            + self.discard_alls;
+        self.n_decks * self.n_colors * (n_numbers + n_actions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*; // bring private functions into scope
+
+    #[test]
+    fn test_deck_spec_standard_n_cards() {
+        assert_eq!(DeckSpec::standard().n_cards(), 108);
+    }
+
+    #[test]
+    fn test_deck_spec_flip_n_cards() {
+        assert_eq!(DeckSpec::flip().n_cards(), 112);
+    }
+
+    #[test]
+    fn test_deck_spec_no_mercy_n_cards() {
+        assert_eq!(DeckSpec::no_mercy().n_cards(), 156);
+    }
+
+    #[test]
+    fn test_deck_spec_builder_n_cards() {
+        let spec = DeckSpec::standard()
+            .n_decks(2)
+            .n_colors(3)
+            .number(0, 2)
+            .skips(0)
+            .wild_draw_4s(2);
+        // per deck: 3 colors * (20 numbers + 2 reverses + 2 draw 2s) + 4 wilds + 2 wild draw 4s
+        assert_eq!(spec.n_cards(), 2 * (3 * 24 + 6));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_deck_spec_n_colors_out_of_range() {
+        DeckSpec::standard().n_colors(5);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_deck_spec_without_decks() {
+        DeckSpec::standard().n_decks(0);
+    }
+
+    #[test]
+    fn test_deck_spec_only_wild_cards() {
+        let spec = DeckSpec::standard()
+            .skips(0)
+            .reverses(0)
+            .draw_2s(0)
+            .number(0, 0);
+        assert!(spec.validate().is_ok());
+        let spec = (1..=9).fold(spec, |spec, number| spec.number(number, 0));
+        assert!(spec.validate().is_err());
+    }
+
+    #[test]
+    fn test_deck_spec_deserialize_invalid() {
+        for s in ["n_colors = 5", "n_colors = 0", "n_decks = 0", "jokers = 2"] {
+            assert!(toml::from_str::<DeckSpec>(s).is_err(), "accepted: {}", s);
+        }
+        let spec: DeckSpec = toml::from_str("n_colors = 3").unwrap();
+        assert_eq!(spec, DeckSpec::standard().n_colors(3));
+    }
+}
@@ -0,0 +1,148 @@
+use crate::{Cards, Dealer};
+use rand::distributions::{Distribution, WeightedIndex};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// relative weights of the number of cards fired by the launcher, indexed by number
+const LAUNCHER_WEIGHTS: [u32; 7] = [50, 15, 12, 10, 7, 4, 2];
+
+/// Source of cards for draw actions, i.e. drawing instead of playing a card and taking
+/// the penalty of draw cards.
+pub trait DrawSource: fmt::Debug {
+    /// Draw cards from `dealer` for `n_draws` draw actions, without drawing more cards
+    /// than are available.
+    fn draw(&self, dealer: &mut Dealer, n_draws: usize) -> Cards;
+}
+
+/// Draw a single card from the deck per draw action.
+#[derive(Debug, Clone, Default)]
+pub struct DeckDraw;
+
+impl DrawSource for DeckDraw {
+    fn draw(&self, dealer: &mut Dealer, n_draws: usize) -> Cards {
+        let n_cards = n_draws.min(dealer.n_available());
+        dealer.draw(n_cards)
+    }
+}
+
+/// Card launcher of Uno Attack, firing a random number of cards per draw action.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "LauncherSpec")]
+pub struct Launcher {
+    pub(crate) weights: Vec<u32>, // relative weights, indexed by number of cards fired
+}
+
+impl Default for Launcher {
+    fn default() -> Self {
+        Self::new(LAUNCHER_WEIGHTS.to_vec())
+    }
+}
+
+impl Launcher {
+    /// Create launcher firing `n` cards with probability proportional to `weights[n]`.
+    pub fn new(weights: Vec<u32>) -> Self {
+        Self::try_new(weights).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Create launcher like [`Launcher::new`], returning an error if it cannot fire any
+    /// card.
+    pub fn try_new(weights: Vec<u32>) -> Result<Self, String> {
+        if !weights.iter().skip(1).any(|&x| x > 0) {
+            return Err(String::from(
+                "launcher must be able to fire at least one card",
+            ));
+        }
+        if WeightedIndex::new(&weights).is_err() {
+            return Err(String::from("invalid launcher weights"));
+        }
+        Ok(Self { weights })
+    }
+}
+
+/// Launcher as written in rules, checked when converted into a [`Launcher`].
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct LauncherSpec {
+    weights: Vec<u32>,
+}
+
+impl Default for LauncherSpec {
+    fn default() -> Self {
+        Self {
+            weights: LAUNCHER_WEIGHTS.to_vec(),
+        }
+    }
+}
+
+impl TryFrom<LauncherSpec> for Launcher {
+    type Error = String;
+
+    fn try_from(spec: LauncherSpec) -> Result<Self, Self::Error> {
+        Self::try_new(spec.weights)
+    }
+}
+
+impl DrawSource for Launcher {
+    fn draw(&self, dealer: &mut Dealer, n_draws: usize) -> Cards {
+        let distribution = WeightedIndex::new(&self.weights).expect("invalid launcher weights");
+        let mut cards = Vec::new();
+        for _ in 0..n_draws {
+            let n_cards = distribution.sample(&mut dealer.rng);
+            let n_cards = n_cards.min(dealer.n_available());
+            cards.extend(dealer.draw(n_cards));
+        }
+        cards
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*; // bring private functions into scope
+    use crate::{Card, Color, DeckSpec, Symbol};
+
+    fn create_dealer(n_cards: usize) -> Dealer {
+        let deck = vec![Card::new(Symbol::Number(1), Some(Color::Red)); n_cards];
+        let pile = vec![Card::new(Symbol::Number(2), Some(Color::Red))];
+        Dealer::from_cards(deck, pile)
+    }
+
+    #[test]
+    fn test_deck_draw_one_card_per_draw() {
+        let mut dealer = create_dealer(5);
+        assert_eq!(DeckDraw.draw(&mut dealer, 2).len(), 2);
+        assert_eq!(DeckDraw.draw(&mut dealer, 4).len(), 3);
+        assert_eq!(dealer.n_available(), 0);
+    }
+
+    #[test]
+    fn test_launcher_fires_cards_per_draw() {
+        let launcher = Launcher::new(vec![0, 0, 1]);
+        let mut dealer = create_dealer(10);
+        assert_eq!(launcher.draw(&mut dealer, 3).len(), 6);
+        assert_eq!(launcher.draw(&mut dealer, 3).len(), 4);
+    }
+
+    #[test]
+    fn test_launcher_is_seeded() {
+        let draw = |seed: u64| {
+            let mut dealer = Dealer::with_seed(&DeckSpec::standard(), seed);
+            Launcher::default().draw(&mut dealer, 10)
+        };
+        assert_eq!(draw(42), draw(42));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_launcher_without_cards() {
+        Launcher::new(vec![1]);
+    }
+
+    #[test]
+    fn test_launcher_deserialize_invalid() {
+        for s in ["weights = [1]", "weights = []", "weights = [0, 0]"] {
+            assert!(toml::from_str::<Launcher>(s).is_err(), "accepted: {}", s);
+        }
+        let launcher: Launcher = toml::from_str("").unwrap();
+        assert_eq!(launcher, Launcher::default());
+    }
+}
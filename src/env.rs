@@ -0,0 +1,505 @@
+use crate::{
+    filter_legal_cards, is_verbose, remove_duplicates, set_verbose, Card, Cards, Color, Direction,
+    DrawMode, Game, GameResult, PlayerView, Rules, SaveWildsStrategy, Strategy, Symbol,
+    MAX_PLAYERS,
+};
+use std::sync::Arc;
+
+// card symbols in the order used for encoding cards and actions
+pub(crate) const SYMBOLS: [Symbol; 26] = [
+    Symbol::Number(0),
+    Symbol::Number(1),
+    Symbol::Number(2),
+    Symbol::Number(3),
+    Symbol::Number(4),
+    Symbol::Number(5),
+    Symbol::Number(6),
+    Symbol::Number(7),
+    Symbol::Number(8),
+    Symbol::Number(9),
+    Symbol::Skip,
+    Symbol::Reverse,
+    Symbol::Draw2,
+    Symbol::Wild,
+    Symbol::WildDraw4,
+    Symbol::Draw1,
+    Symbol::Flip,
+    Symbol::WildDraw2,
+    Symbol::Draw5,
+    Symbol::SkipEveryone,
+    Symbol::WildDrawColor,
+    Symbol::DiscardAll,
+    Symbol::WildDraw6,
+    Symbol::WildDraw10,
+    Symbol::WildReverseDraw4,
+    Symbol::WildColorRoulette,
+];
+
+// card colors of both sides in the order used for encoding cards and actions
+pub(crate) const COLORS: [Color; 8] = [
+    Color::Red,
+    Color::Blue,
+    Color::Green,
+    Color::Yellow,
+    Color::Pink,
+    Color::Teal,
+    Color::Orange,
+    Color::Purple,
+];
+
+const N_FACES: usize = SYMBOLS.len() * (COLORS.len() + 1); // including wild cards without color
+
+// number of seeds tried for a game reaching the agent's first turn
+const MAX_RESETS: u64 = 100;
+
+/// Number of actions, i.e. playing a card of each symbol in each color, drawing and passing.
+pub const N_ACTIONS: usize = SYMBOLS.len() * COLORS.len() + 2;
+
+/// Length of observations encoding the view of a player.
+pub const OBSERVATION_SIZE: usize = 4 * N_FACES + COLORS.len() + 2 * MAX_PLAYERS + 3;
+
+/// Action of the agent in the environment, identified by its index in the action space.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// Play card with the given symbol and color, choosing the color of wild cards.
+    Play(Symbol, Color),
+    /// Draw instead of playing a card from hand.
+    Draw,
+    /// Keep the cards just drawn, or end the turn if no card can be drawn.
+    Pass,
+}
+
+impl Action {
+    /// Get action at `index` in the action space.
+    pub fn from_index(index: usize) -> Self {
+        assert!(index < N_ACTIONS, "action index out of range");
+        match index {
+            _ if index == N_ACTIONS - 2 => Action::Draw,
+            _ if index == N_ACTIONS - 1 => Action::Pass,
+            _ => Action::Play(SYMBOLS[index / COLORS.len()], COLORS[index % COLORS.len()]),
+        }
+    }
+
+    /// Get index of action in the action space.
+    pub fn index(&self) -> usize {
+        match self {
+            Action::Play(symbol, color) => symbol_index(symbol) * COLORS.len() + color_index(color),
+            Action::Draw => N_ACTIONS - 2,
+            Action::Pass => N_ACTIONS - 1,
+        }
+    }
+}
+
+fn symbol_index(symbol: &Symbol) -> usize {
+    SYMBOLS
+        .iter()
+        .position(|x| x == symbol)
+        .expect("unknown symbol")
+}
+
+fn color_index(color: &Color) -> usize {
+    COLORS
+        .iter()
+        .position(|x| x == color)
+        .expect("unknown color")
+}
+
+/// Get index of the face of `card`, where wild cards have no color.
+fn face_index(card: &Card) -> usize {
+    let color = match card.is_wild() {
+        true => COLORS.len(),
+        false => color_index(&card.color.expect("card without color")),
+    };
+    symbol_index(&card.symbol) * (COLORS.len() + 1) + color
+}
+
+/// Get actions playing one of `legal_cards`, with wild cards played in each color.
+fn play_actions(legal_cards: &[Card]) -> Vec<Action> {
+    let mut actions = vec![];
+    for card in legal_cards {
+        match card.is_wild() {
+            true => actions.extend(card.side.colors().map(|x| Action::Play(card.symbol, x))),
+            false => actions.push(Action::Play(card.symbol, card.color.expect("no color"))),
+        }
+    }
+    actions
+}
+
+/// Run `f` without printing game progress.
+fn silently<T>(f: impl FnOnce() -> T) -> T {
+    let verbose = is_verbose();
+    set_verbose(false);
+    let output = f();
+    set_verbose(verbose);
+    output
+}
+
+/// Encode `view` as fixed-size observation of length [`OBSERVATION_SIZE`], with counts of
+/// the cards in hand, the cards just `drawn` among them and the cards on the pile, the
+/// top card, the hand sizes and partners in seat
+/// order starting with the player, the direction of play, pending draws and the number
+/// of cards in the deck.
+pub fn encode_view(view: &PlayerView, drawn: &[Card]) -> Vec<f32> {
+    let mut observation = vec![0.0; OBSERVATION_SIZE];
+    let (hand, rest) = observation.split_at_mut(N_FACES);
+    let (new_cards, rest) = rest.split_at_mut(N_FACES);
+    let (pile, rest) = rest.split_at_mut(N_FACES);
+    let (top_card, rest) = rest.split_at_mut(N_FACES);
+    let (top_color, rest) = rest.split_at_mut(COLORS.len());
+    let (hand_sizes, rest) = rest.split_at_mut(MAX_PLAYERS);
+    let (partners, rest) = rest.split_at_mut(MAX_PLAYERS);
+
+    for card in &view.hand {
+        hand[face_index(card)] += 1.0;
+    }
+    for card in drawn {
+        new_cards[face_index(card)] += 1.0;
+    }
+    for card in &view.pile {
+        pile[face_index(card)] += 1.0;
+    }
+    top_card[face_index(&view.top_card)] = 1.0;
+    top_color[color_index(&view.top_card.color.expect("top card without color"))] = 1.0;
+
+    let n_players = view.hand_sizes.len();
+    for seat in 0..n_players {
+        let other = (view.index + seat) % n_players;
+        hand_sizes[seat] = view.hand_sizes[other] as f32;
+        partners[seat] = f32::from(u8::from(view.is_partner(other)));
+    }
+    let n_other_cards = view.hand_sizes.iter().sum::<usize>() - view.hand.len();
+    rest[0] = f32::from(u8::from(view.direction == Direction::Reverse));
+    rest[1] = view.pending_draw as f32;
+    rest[2] = (view.unknown_cards.len() - n_other_cards) as f32;
+    observation
+}
+
+/// Reinforcement learning environment for training an agent against the game engine,
+/// where the agent takes the turns of one player and the other players follow a
+/// strategy. The agent plays a card from hand or draws, and then plays or keeps the
+/// cards just drawn, while its other decisions, like responding to draw cards or
+/// choosing the color of a wild first card, are made by a greedy strategy.
+pub struct UnoEnv {
+    n_players: usize,
+    rules: Rules,
+    agent: usize,
+    opponent: Arc<dyn Strategy>,
+    game: Option<Game>,
+    drawn: Cards, // cards just drawn by the agent, which may be played
+}
+
+impl UnoEnv {
+    /// Create environment for games of `n_players` with `rules`, where the agent plays
+    /// the first player against players saving wild cards.
+    pub fn new(n_players: usize, rules: Rules) -> Self {
+        assert!(
+            (2..=MAX_PLAYERS).contains(&n_players),
+            "number of players must be between 2 and {}",
+            MAX_PLAYERS
+        );
+        Self {
+            n_players,
+            rules,
+            agent: 0,
+            opponent: Arc::new(SaveWildsStrategy {}),
+            game: None,
+            drawn: vec![],
+        }
+    }
+
+    /// Let the agent play the player at `index`.
+    pub fn agent(mut self, index: usize) -> Self {
+        assert!(index < self.n_players, "agent index out of range");
+        self.agent = index;
+        self
+    }
+
+    /// Let the other players follow `strategy`.
+    pub fn opponent(mut self, strategy: Box<dyn Strategy>) -> Self {
+        self.opponent = Arc::from(strategy);
+        self
+    }
+
+    /// Start new game seeded with `seed`, with the dealer chosen by the seed, and
+    /// return the first observation of the agent. If the game is over before the
+    /// agent's first turn, e.g. when the agent is eliminated, the next seed is used.
+    pub fn reset(&mut self, seed: u64) -> Vec<f32> {
+        self.drawn.clear();
+        for n_resets in 0..MAX_RESETS {
+            let seed = seed.wrapping_add(n_resets);
+            let dealer = (seed % self.n_players as u64) as usize;
+            let mut game = Game::with_seed(self.n_players, dealer, self.rules.clone(), seed);
+            for (index, player) in game.players.players.iter_mut().enumerate() {
+                player.strategy = match index == self.agent {
+                    true => Arc::new(SaveWildsStrategy {}),
+                    false => self.opponent.clone(),
+                };
+            }
+
+            // let the other players play until it is the agent's turn
+            let result = silently(|| {
+                game.start();
+                Self::play_until_turn(&mut game, self.agent)
+            });
+            if result.is_none() {
+                self.game = Some(game);
+                return self.observation();
+            }
+        }
+        panic!("no game reached the agent's turn in {} seeds", MAX_RESETS);
+    }
+
+    /// Take `action` given by its index in the action space and let the other players
+    /// play until it is the agent's turn again, returning the next observation, the
+    /// reward and whether the game is over. The reward is 1 if the agent or its partner
+    /// wins, -1 if another player wins and 0 while the game continues.
+    pub fn step(&mut self, action: usize) -> (Vec<f32>, f32, bool) {
+        let action = Action::from_index(action);
+        assert!(self.legal_actions()[action.index()], "illegal action");
+        let mut game = self.game.take().expect("game over, reset environment");
+        let result = silently(|| self.take_action(&mut game, action));
+
+        let observation = encode_view(&game.view(self.agent), &self.drawn);
+        let is_done = result.is_some();
+        let reward = match result {
+            Some(result) => match game
+                .players
+                .team_members(self.agent)
+                .contains(&result.winner)
+            {
+                true => 1.0,
+                false => -1.0,
+            },
+            None => 0.0,
+        };
+        if !is_done {
+            self.game = Some(game);
+        }
+        (observation, reward, is_done)
+    }
+
+    /// Get mask of the actions in the action space which are legal for the agent.
+    pub fn legal_actions(&self) -> Vec<bool> {
+        let game = self.game.as_ref().expect("game over, reset environment");
+        let top_card = game.top_card();
+        let wild_draw_4 = self.rules.wild_draw_4;
+        let mut mask = vec![false; N_ACTIONS];
+
+        // play one of the cards just drawn or keep them, otherwise play from hand or draw
+        let (cards, alternative) = match self.drawn.is_empty() {
+            false => (self.drawn.clone(), Action::Pass),
+            true if game.dealer.n_available() > 0 => (self.hand(game).to_vec(), Action::Draw),
+            true => (self.hand(game).to_vec(), Action::Pass),
+        };
+        if !cards.is_empty() {
+            let legal_cards = remove_duplicates(filter_legal_cards(cards, top_card, wild_draw_4));
+            for action in play_actions(&legal_cards) {
+                mask[action.index()] = true;
+            }
+        }
+        mask[alternative.index()] = true;
+        mask
+    }
+
+    /// Get view of the game for the agent.
+    pub fn view(&self) -> PlayerView {
+        let game = self.game.as_ref().expect("game over, reset environment");
+        game.view(self.agent)
+    }
+
+    fn observation(&self) -> Vec<f32> {
+        encode_view(&self.view(), &self.drawn)
+    }
+
+    fn hand<'a>(&self, game: &'a Game) -> &'a [Card] {
+        &game.players.players[self.agent].hand
+    }
+
+    /// Take `action` for the agent and let the other players play until it is the
+    /// agent's turn again, returning the result if the game is over.
+    fn take_action(&mut self, game: &mut Game, action: Action) -> Option<GameResult> {
+        let agent = self.agent;
+        let n_recycles = game.dealer.n_recycles;
+        match action {
+            Action::Play(symbol, color) => {
+                let hand = &game.players.players[agent].hand;
+                let card = hand
+                    .iter()
+                    .find(|x| x.symbol == symbol && (x.is_wild() || x.color == Some(color)));
+                let mut card = *card.expect("card not in hand");
+                card.color = Some(color);
+                game.players.players[agent].remove_from_hand(&card);
+                self.drawn.clear();
+                if let Some(result) = game.play_card(agent, card) {
+                    return Some(result);
+                }
+            }
+            Action::Draw | Action::Pass => {
+                // keep drawing until drawing a legal card, depending on the draw mode
+                let is_drawing = match action {
+                    Action::Draw => true,
+                    _ => !self.drawn.is_empty() && self.rules.draw_mode == DrawMode::UntilPlayable,
+                };
+                self.drawn.clear();
+                // the other players learn the agent's voids only when it cannot play
+                let top_card = game.top_card();
+                let player = &mut game.players.players[agent];
+                if action == Action::Draw
+                    && filter_legal_cards(player.hand.clone(), top_card, self.rules.wild_draw_4)
+                        .is_empty()
+                {
+                    player.observe_draw(&top_card);
+                }
+                while is_drawing && game.dealer.n_available() > 0 {
+                    let cards = game.draw_cards(agent);
+                    if cards.is_empty() {
+                        break;
+                    }
+                    game.players.players[agent].take_cards(cards.clone());
+                    let top_card = game.top_card();
+                    let is_legal =
+                        !filter_legal_cards(cards.clone(), top_card, self.rules.wild_draw_4)
+                            .is_empty();
+                    if is_legal {
+                        self.drawn = cards;
+                        game.record_recycles(n_recycles);
+                        return None;
+                    }
+                    if self.rules.draw_mode == DrawMode::DrawOne {
+                        break;
+                    }
+                }
+            }
+        }
+        game.record_recycles(n_recycles);
+        game.eliminate_players()
+            .or_else(|| Self::play_until_turn(game, agent))
+    }
+
+    /// Let the other players play until it is the turn of the player at `agent`,
+    /// returning the result if the game is over.
+    fn play_until_turn(game: &mut Game, agent: usize) -> Option<GameResult> {
+        loop {
+            let is_turn = game.players.cycle.peek_next() == agent
+                && game.pending_draw == 0
+                && game.pending_draw_color.is_none()
+                && !game.pending_roulette;
+            if is_turn {
+                let index = game.players.next_index();
+                game.start_turn(index);
+                return None;
+            }
+            if let Some(result) = game.step() {
+                return Some(result);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*; // bring private functions into scope
+    use crate::{Event, GameStats};
+    use rand::seq::IteratorRandom;
+    use rstest::rstest;
+
+    const MAX_STEPS: usize = 10_000;
+
+    #[test]
+    fn test_action_index() {
+        for index in 0..N_ACTIONS {
+            assert_eq!(Action::from_index(index).index(), index);
+        }
+        assert_eq!(Action::from_index(N_ACTIONS - 2), Action::Draw);
+        assert_eq!(
+            Action::from_index(0),
+            Action::Play(Symbol::Number(0), Color::Red)
+        );
+    }
+
+    #[test]
+    fn test_reset_is_seeded() {
+        let mut env = UnoEnv::new(4, Rules::official());
+        let observation = env.reset(42);
+        assert_eq!(observation.len(), OBSERVATION_SIZE);
+        assert_eq!(env.reset(42), observation);
+    }
+
+    #[test]
+    fn test_encode_view() {
+        let mut env = UnoEnv::new(4, Rules::official());
+        env.reset(42);
+        let view = env.view();
+        let observation = encode_view(&view, &[]);
+        let hand: f32 = observation[..N_FACES].iter().sum();
+        assert_eq!(hand, view.hand.len() as f32);
+        let drawn: f32 = observation[N_FACES..][..N_FACES].iter().sum();
+        assert_eq!(drawn, 0.0);
+        let hand_sizes = &observation[4 * N_FACES + COLORS.len()..][..MAX_PLAYERS];
+        assert_eq!(hand_sizes[0], view.hand.len() as f32);
+        assert_eq!(hand_sizes[4..], [0.0; MAX_PLAYERS - 4]);
+    }
+
+    #[test]
+    fn test_observe_drawn_cards() {
+        let mut env = UnoEnv::new(4, Rules::official());
+        env.reset(42);
+        while env.legal_actions()[Action::Draw.index()] {
+            let (observation, _, is_done) = env.step(Action::Draw.index());
+            let drawn: f32 = observation[N_FACES..][..N_FACES].iter().sum();
+            assert_eq!(drawn, env.drawn.len() as f32);
+            if is_done || !env.drawn.is_empty() {
+                return;
+            }
+        }
+    }
+
+    #[test]
+    fn test_record_events_of_agent() {
+        let mut env = UnoEnv::new(2, Rules::official()).agent(1);
+        env.reset(42);
+        env.step(Action::Draw.index());
+        let game = env.game.as_ref().expect("game over");
+        let stats = GameStats::from_events(2, &game.events);
+        assert_eq!(stats.players[1].n_drawn, 1);
+        assert!(game.events.contains(&Event::TurnStarted {
+            player: 1,
+            n_cards: 7
+        }));
+    }
+
+    #[rstest]
+    #[case::official(Rules::official())]
+    #[case::house_classic(Rules::house_classic())]
+    #[case::no_mercy(Rules::no_mercy())]
+    #[case::flip(Rules::flip())]
+    #[case::attack(Rules::attack())]
+    #[case::partners(Rules::partners())]
+    fn test_play_random_legal_actions(#[case] rules: Rules) {
+        let mut env = UnoEnv::new(4, rules).agent(1);
+        env.reset(rand::random());
+        let mut rng = rand::thread_rng();
+        for _ in 0..MAX_STEPS {
+            let mask = env.legal_actions();
+            let action = (0..N_ACTIONS).filter(|&x| mask[x]).choose(&mut rng);
+            let (observation, reward, is_done) = env.step(action.expect("no legal action"));
+            assert_eq!(observation.len(), OBSERVATION_SIZE);
+            if is_done {
+                assert!(reward == 1.0 || reward == -1.0);
+                return;
+            }
+            assert_eq!(reward, 0.0);
+        }
+        panic!("game not over after {} steps", MAX_STEPS);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_step_illegal_action() {
+        let mut env = UnoEnv::new(2, Rules::official());
+        env.reset(42);
+        let mask = env.legal_actions();
+        let action = (0..N_ACTIONS).find(|&x| !mask[x]);
+        env.step(action.unwrap());
+    }
+}
@@ -0,0 +1,230 @@
+use crate::{Card, Cards, Color, Play, PlayerView, Side, Strategy, Symbol};
+
+const NEAR_UNO: usize = 2; // number of cards at which a player is close to going out
+
+/// Get color of `side` held most often in `hand`, preferring earlier colors on ties.
+pub(crate) fn most_held_color(hand: &[Card], side: Side) -> Color {
+    let count = |color: Color| hand.iter().filter(|x| x.color == Some(color)).count();
+    let colors = side.colors();
+    colors
+        .into_iter()
+        .rev()
+        .max_by_key(|&color| count(color))
+        .expect("empty colors")
+}
+
+/// Select color of wild `card` as the color held most often in `hand`.
+pub(crate) fn with_color(mut card: Card, hand: &[Card]) -> Card {
+    if card.is_wild() {
+        debug_assert!(card.color.is_none());
+        card.color = Some(most_held_color(hand, card.side));
+    }
+    card
+}
+
+/// Check if `card` is an action card, i.e. neither a number nor a plain wild card.
+fn is_action(card: &Card) -> bool {
+    !matches!(card.symbol, Symbol::Number(_) | Symbol::Wild)
+}
+
+/// Select first card from `legal_cards` with the highest `key`, coloring wild cards
+/// according to `hand`.
+fn select_by_key<K: Ord>(legal_cards: Cards, hand: &[Card], key: impl Fn(&Card) -> K) -> Play {
+    debug_assert!(!legal_cards.is_empty());
+    let card = legal_cards.iter().rev().max_by_key(|x| key(x))?;
+    Some(with_color(*card, hand))
+}
+
+/// Greedy strategy playing the card with the most points first.
+#[derive(Debug)]
+pub struct HighestPointsStrategy {}
+
+impl Strategy for HighestPointsStrategy {
+    fn select_card(&self, legal_cards: Cards) -> Play {
+        let hand = legal_cards.clone();
+        select_by_key(legal_cards, &hand, |x| x.points())
+    }
+
+    fn select_card_with_view(&self, legal_cards: Cards, view: &PlayerView) -> Play {
+        select_by_key(legal_cards, &view.hand, |x| x.points())
+    }
+
+    fn select_color(&self, hand: &Cards, side: Side) -> Color {
+        most_held_color(hand, side)
+    }
+}
+
+/// Greedy strategy playing cards of the color held most often first.
+#[derive(Debug)]
+pub struct MostHeldColorStrategy {}
+
+impl MostHeldColorStrategy {
+    fn select(legal_cards: Cards, hand: &[Card]) -> Play {
+        let count = |card: &Card| match card.is_wild() {
+            true => 0,
+            false => hand.iter().filter(|x| x.color == card.color).count(),
+        };
+        select_by_key(legal_cards, hand, |x| (count(x), x.points()))
+    }
+}
+
+impl Strategy for MostHeldColorStrategy {
+    fn select_card(&self, legal_cards: Cards) -> Play {
+        let hand = legal_cards.clone();
+        Self::select(legal_cards, &hand)
+    }
+
+    fn select_card_with_view(&self, legal_cards: Cards, view: &PlayerView) -> Play {
+        Self::select(legal_cards, &view.hand)
+    }
+
+    fn select_color(&self, hand: &Cards, side: Side) -> Color {
+        most_held_color(hand, side)
+    }
+}
+
+/// Greedy strategy saving wild cards for last, otherwise playing the card with the
+/// most points.
+#[derive(Debug)]
+pub struct SaveWildsStrategy {}
+
+impl SaveWildsStrategy {
+    fn select(legal_cards: Cards, hand: &[Card]) -> Play {
+        select_by_key(legal_cards, hand, |x| (!x.is_wild(), x.points()))
+    }
+}
+
+impl Strategy for SaveWildsStrategy {
+    fn select_card(&self, legal_cards: Cards) -> Play {
+        let hand = legal_cards.clone();
+        Self::select(legal_cards, &hand)
+    }
+
+    fn select_card_with_view(&self, legal_cards: Cards, view: &PlayerView) -> Play {
+        Self::select(legal_cards, &view.hand)
+    }
+
+    fn select_color(&self, hand: &Cards, side: Side) -> Color {
+        most_held_color(hand, side)
+    }
+}
+
+/// Greedy strategy saving action cards until the next player is an opponent close to
+/// going out, then playing the action card making them draw the most cards first.
+#[derive(Debug)]
+pub struct DumpActionsStrategy {}
+
+impl Strategy for DumpActionsStrategy {
+    fn select_card(&self, legal_cards: Cards) -> Play {
+        let hand = legal_cards.clone();
+        select_by_key(legal_cards, &hand, |x| (!is_action(x), x.points()))
+    }
+
+    fn select_card_with_view(&self, legal_cards: Cards, view: &PlayerView) -> Play {
+        let next = view.next;
+        let is_near_uno = view.is_opponent(next) && view.hand_sizes[next] <= NEAR_UNO;
+        match is_near_uno {
+            true => select_by_key(legal_cards, &view.hand, |x| {
+                (is_action(x), x.draw_amount(), x.points())
+            }),
+            false => select_by_key(legal_cards, &view.hand, |x| (!is_action(x), x.points())),
+        }
+    }
+
+    fn select_color(&self, hand: &Cards, side: Side) -> Color {
+        most_held_color(hand, side)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*; // bring private functions into scope
+    use crate::{Direction, HandInfo};
+
+    fn create_view(hand: Cards, hand_sizes: Vec<usize>) -> PlayerView {
+        let hand_infos = vec![HandInfo::default(); hand_sizes.len()];
+        PlayerView {
+            index: 0,
+            hand,
+            next: 1,
+            hand_sizes,
+            teams: None,
+            top_card: Card::new(Symbol::Number(1), Some(Color::Red)),
+            pile: vec![],
+            unknown_cards: vec![],
+            direction: Direction::Forward,
+            pending_draw: 0,
+            hand_infos,
+        }
+    }
+
+    #[test]
+    fn test_most_held_color() {
+        let hand = vec![
+            Card::new(Symbol::Number(1), Some(Color::Blue)),
+            Card::new(Symbol::Number(2), Some(Color::Green)),
+            Card::new(Symbol::Skip, Some(Color::Green)),
+            Card::new(Symbol::Wild, None),
+        ];
+        assert_eq!(most_held_color(&hand, Side::Light), Color::Green);
+        assert_eq!(most_held_color(&[], Side::Light), Color::Red);
+    }
+
+    #[test]
+    fn test_highest_points_strategy() {
+        let cards = vec![
+            Card::new(Symbol::Number(9), Some(Color::Red)),
+            Card::new(Symbol::Skip, Some(Color::Red)),
+        ];
+        let card = HighestPointsStrategy {}.select_card(cards.clone());
+        assert_eq!(card, Some(cards[1]));
+    }
+
+    #[test]
+    fn test_most_held_color_strategy() {
+        let hand = vec![
+            Card::new(Symbol::Number(1), Some(Color::Red)),
+            Card::new(Symbol::Number(2), Some(Color::Blue)),
+            Card::new(Symbol::Number(3), Some(Color::Blue)),
+            Card::new(Symbol::Wild, None),
+        ];
+        let legal_cards = vec![hand[0], hand[1], hand[3]];
+        let view = create_view(hand.clone(), vec![4, 7]);
+        let card = MostHeldColorStrategy {}.select_card_with_view(legal_cards, &view);
+        assert_eq!(card, Some(hand[1]));
+    }
+
+    #[test]
+    fn test_save_wilds_strategy() {
+        let hand = vec![
+            Card::new(Symbol::WildDraw4, None),
+            Card::new(Symbol::Number(1), Some(Color::Yellow)),
+        ];
+        let view = create_view(hand.clone(), vec![2, 7]);
+        let strategy = SaveWildsStrategy {};
+        let card = strategy.select_card_with_view(hand.clone(), &view);
+        assert_eq!(card, Some(hand[1]));
+
+        // wild card is played last, with the color held most often
+        let card = strategy.select_card_with_view(vec![hand[0]], &view);
+        assert_eq!(card.unwrap().color, Some(Color::Yellow));
+    }
+
+    #[test]
+    fn test_dump_actions_strategy() {
+        let hand = vec![
+            Card::new(Symbol::Number(9), Some(Color::Red)),
+            Card::new(Symbol::Skip, Some(Color::Red)),
+            Card::new(Symbol::Draw2, Some(Color::Red)),
+        ];
+        let strategy = DumpActionsStrategy {};
+
+        let view = create_view(hand.clone(), vec![3, 7]);
+        let card = strategy.select_card_with_view(hand.clone(), &view);
+        assert_eq!(card, Some(hand[0]));
+
+        let view = create_view(hand.clone(), vec![3, 1]);
+        let card = strategy.select_card_with_view(hand.clone(), &view);
+        assert_eq!(card, Some(hand[2]));
+    }
+}
@@ -0,0 +1,487 @@
+use crate::greedy::{most_held_color, with_color};
+use crate::{
+    is_verbose, set_verbose, Card, Cards, Color, Dealer, Direction, Game, Play, PlayerView, Rules,
+    SaveWildsStrategy, Side, Strategy,
+};
+use rand::seq::SliceRandom;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+const N_ITERATIONS: usize = 1000; // default number of simulated games per decision
+const EXPLORATION: f64 = 0.7; // exploration constant of the upper confidence bound
+const MAX_STEPS: usize = 1000; // number of turns after which a simulated game is a loss
+
+/// Information-set Monte Carlo tree search, sampling the unknown cards of the other
+/// players and the deck for each simulated game consistent with what is known about their
+/// hands, with the other players and the simulation beyond the tree following a greedy
+/// strategy.
+#[derive(Debug, Clone)]
+pub struct IsmctsStrategy {
+    rules: Rules,
+    n_iterations: usize,
+    time_budget: Option<Duration>,
+    exploration: f64,
+}
+
+impl IsmctsStrategy {
+    /// Create strategy for games played with `rules`.
+    pub fn new(rules: Rules) -> Self {
+        Self {
+            rules,
+            n_iterations: N_ITERATIONS,
+            time_budget: None,
+            exploration: EXPLORATION,
+        }
+    }
+
+    /// Simulate at most `n_iterations` games per decision.
+    pub fn n_iterations(mut self, n_iterations: usize) -> Self {
+        assert!(n_iterations > 0, "number of iterations must be positive");
+        self.n_iterations = n_iterations;
+        self
+    }
+
+    /// Stop simulating games after `time_budget` per decision.
+    pub fn time_budget(mut self, time_budget: Duration) -> Self {
+        self.time_budget = Some(time_budget);
+        self
+    }
+
+    /// Set `exploration` constant of the upper confidence bound.
+    pub fn exploration(mut self, exploration: f64) -> Self {
+        self.exploration = exploration;
+        self
+    }
+
+    /// Search card to play from `legal_cards` given the `view` of the game.
+    fn search(&self, legal_cards: Cards, view: &PlayerView) -> Card {
+        let view = &take_drawn_cards(view, &legal_cards);
+        let tree = Rc::new(RefCell::new(Tree::new(self.exploration)));
+        let start = Instant::now();
+
+        // simulations are not printed, restoring the previous setting afterwards
+        let verbose = is_verbose();
+        set_verbose(false);
+        for _ in 0..self.n_iterations {
+            if let Some(time_budget) = self.time_budget {
+                if start.elapsed() >= time_budget {
+                    break;
+                }
+            }
+            self.simulate(&legal_cards, view, &tree);
+        }
+        set_verbose(verbose);
+
+        let tree = tree.borrow();
+        tree.most_visited(&legal_cards)
+    }
+
+    /// Simulate a single game from a sampled state consistent with `view`, selecting
+    /// the first card from `legal_cards` in the `tree` and updating it with the outcome.
+    fn simulate(&self, legal_cards: &[Card], view: &PlayerView, tree: &Rc<RefCell<Tree>>) {
+        let mut game = self.determinize(view);
+        let index = view.index;
+        game.set_strategy(index, Box::new(TreeStrategy { tree: tree.clone() }));
+        tree.borrow_mut().start();
+
+        // play first card selected from the tree, including cards just drawn
+        let card = tree.borrow_mut().select(legal_cards);
+        let card = with_color(card.expect("empty legal cards"), &view.hand);
+        let player = &mut game.players.players[index];
+        if player
+            .hand
+            .iter()
+            .any(|x| x.is_equal_ignore_wild_color(&card))
+        {
+            player.remove_from_hand(&card);
+        }
+        let mut result = game.play_card(index, card);
+
+        // play until the game is over
+        let mut n_steps = 0;
+        while result.is_none() && n_steps < MAX_STEPS {
+            result = game.step();
+            n_steps += 1;
+        }
+        let reward = match result {
+            Some(result) if result.winner == index || view.is_partner(result.winner) => 1.0,
+            _ => 0.0,
+        };
+        tree.borrow_mut().update(reward);
+    }
+
+    /// Sample game consistent with `view`, dealing the unknown cards randomly to the
+    /// other players and the deck, with the player at `view.index` to play next. Only
+    /// pending draws are carried over, as cards are never selected while a draw color
+    /// or the color roulette is pending, and a wild draw 4 that was stacked on can no
+    /// longer be challenged.
+    fn determinize(&self, view: &PlayerView) -> Game {
+        let (hands, unknown_cards) = deal(view);
+        let dealer = Dealer::from_cards(unknown_cards, view.pile.clone());
+        let mut game = Game::from_state(dealer, hands, view.index, self.rules.clone());
+        for index in 0..view.hand_sizes.len() {
+            game.set_strategy(index, Box::new(SaveWildsStrategy {}));
+        }
+
+        // players without cards have been eliminated
+        for (other, &n_cards) in view.hand_sizes.iter().enumerate() {
+            if n_cards == 0 && other != view.index {
+                game.players.eliminate(other);
+            }
+        }
+
+        // continue with the player's turn in the current direction of play
+        game.players.next_index();
+        if view.direction == Direction::Reverse {
+            game.players.cycle.reverse();
+        }
+        game.pending_draw = view.pending_draw;
+        game
+    }
+}
+
+impl Strategy for IsmctsStrategy {
+    /// Select card without further information, saving wild cards.
+    fn select_card(&self, legal_cards: Cards) -> Play {
+        SaveWildsStrategy {}.select_card(legal_cards)
+    }
+
+    fn select_card_with_view(&self, legal_cards: Cards, view: &PlayerView) -> Play {
+        let card = self.search(remove_wild_colors(legal_cards), view);
+        Some(with_color(card, &view.hand))
+    }
+
+    fn select_color(&self, hand: &Cards, side: Side) -> Color {
+        most_held_color(hand, side)
+    }
+}
+
+/// Get `view` with the cards of `legal_cards` just drawn, which are not in hand yet but
+/// among the unknown cards, moved into the hand.
+fn take_drawn_cards(view: &PlayerView, legal_cards: &[Card]) -> PlayerView {
+    let mut view = view.clone();
+    for card in legal_cards {
+        if !view.hand.iter().any(|x| x.is_equal_ignore_wild_color(card)) {
+            let position = view
+                .unknown_cards
+                .iter()
+                .position(|x| x.is_equal_ignore_wild_color(card));
+            if let Some(position) = position {
+                let card = view.unknown_cards.remove(position);
+                view.hand.push(card);
+            }
+        }
+    }
+    view.hand_sizes[view.index] = view.hand.len();
+    view
+}
+
+/// Deal the unknown cards of `view` randomly to the other players, consistent with what
+/// is known about their hands, and return all hands and the cards left for the deck.
+/// Players get the cards they are known to hold first, then cards outside of their voids
+/// except for the cards taken since, see [`HandInfo`](crate::HandInfo).
+fn deal(view: &PlayerView) -> (Vec<Cards>, Cards) {
+    let mut unknown_cards = view.unknown_cards.clone();
+    unknown_cards.shuffle(&mut rand::thread_rng());
+    let mut hands = vec![vec![]; view.hand_sizes.len()];
+    hands[view.index] = view.hand.clone();
+    let mut others: Vec<usize> = (0..hands.len()).filter(|&x| x != view.index).collect();
+
+    // take known cards out of the unknown cards
+    for &other in &others {
+        let known_cards = &view.hand_infos[other].known_cards;
+        for card in known_cards.iter().take(view.hand_sizes[other]) {
+            let position = unknown_cards
+                .iter()
+                .position(|x| x.is_equal_ignore_wild_color(card));
+            if let Some(position) = position {
+                hands[other].push(unknown_cards.remove(position));
+            }
+        }
+    }
+
+    // deal cards outside of voids, starting with the players with the most voids, and
+    // any card if there is none left
+    others.sort_by_key(|&x| std::cmp::Reverse(view.hand_infos[x].voids.len()));
+    for &other in &others {
+        let info = &view.hand_infos[other];
+        let n_cards = view.hand_sizes[other].saturating_sub(hands[other].len());
+        for _ in 0..n_cards.saturating_sub(info.n_free) {
+            let position = unknown_cards
+                .iter()
+                .position(|x| !x.color.is_some_and(|color| info.voids.contains(&color)))
+                .unwrap_or(0);
+            hands[other].push(unknown_cards.remove(position));
+        }
+    }
+    for &other in &others {
+        let n_cards = view.hand_sizes[other] - hands[other].len();
+        hands[other].extend(unknown_cards.drain(..n_cards));
+    }
+    (hands, unknown_cards)
+}
+
+/// Reset color of wild cards, so that they can be compared across simulations.
+fn remove_wild_colors(cards: Cards) -> Cards {
+    cards
+        .into_iter()
+        .map(|mut x| {
+            if x.is_wild() {
+                x.color = None;
+            }
+            x
+        })
+        .collect()
+}
+
+/// Node of the search tree, reached by playing a card.
+#[derive(Debug, Default)]
+struct Node {
+    n_visits: u32,
+    n_available: u32, // number of visits of the parent in which the card was legal
+    reward: f64,
+    children: Vec<(Card, usize)>,
+}
+
+/// Search tree over the cards played by the searching player, with nodes stored in an
+/// arena and the path of the current simulation.
+#[derive(Debug)]
+struct Tree {
+    nodes: Vec<Node>,
+    path: Vec<usize>,
+    is_expanded: bool, // whether a new node was added in the current simulation
+    exploration: f64,
+}
+
+impl Tree {
+    fn new(exploration: f64) -> Self {
+        Self {
+            nodes: vec![Node::default()],
+            path: vec![],
+            is_expanded: false,
+            exploration,
+        }
+    }
+
+    /// Start new simulation at the root.
+    fn start(&mut self) {
+        self.path = vec![0];
+        self.is_expanded = false;
+    }
+
+    /// Select card from `legal_cards` in the current node, expanding a random untried
+    /// card or following the upper confidence bound, or return `None` if the current
+    /// simulation has left the tree.
+    fn select(&mut self, legal_cards: &[Card]) -> Option<Card> {
+        if self.is_expanded {
+            return None;
+        }
+        let parent = *self.path.last().expect("simulation not started");
+        let children = self.nodes[parent].children.clone();
+        let is_legal = |card: &Card| {
+            legal_cards
+                .iter()
+                .any(|x| x.is_equal_ignore_wild_color(card))
+        };
+        let is_child = |card: &Card| {
+            children
+                .iter()
+                .any(|(x, _)| x.is_equal_ignore_wild_color(card))
+        };
+
+        // children of legal cards were available in this visit
+        let legal_children: Vec<(Card, usize)> = children
+            .iter()
+            .copied()
+            .filter(|(x, _)| is_legal(x))
+            .collect();
+        for &(_, child) in legal_children.iter() {
+            self.nodes[child].n_available += 1;
+        }
+
+        // expand untried card
+        let untried: Vec<&Card> = legal_cards.iter().filter(|x| !is_child(x)).collect();
+        if let Some(&&card) = untried.choose(&mut rand::thread_rng()) {
+            let child = self.nodes.len();
+            self.nodes.push(Node::default());
+            self.nodes[parent].children.push((card, child));
+            self.path.push(child);
+            self.is_expanded = true;
+            return Some(card);
+        }
+
+        // otherwise follow upper confidence bound among available children
+        let (card, child) = *legal_children
+            .iter()
+            .max_by(|a, b| self.ucb(a.1).total_cmp(&self.ucb(b.1)))
+            .expect("no legal children");
+        self.path.push(child);
+        Some(card)
+    }
+
+    /// Get upper confidence bound of node at `index`.
+    fn ucb(&self, index: usize) -> f64 {
+        let node = &self.nodes[index];
+        let n_visits = node.n_visits.max(1) as f64;
+        let n_available = node.n_available.max(1) as f64;
+        node.reward / n_visits + self.exploration * (n_available.ln() / n_visits).sqrt()
+    }
+
+    /// Update nodes on the path of the current simulation with `reward`.
+    fn update(&mut self, reward: f64) {
+        for &index in self.path.iter() {
+            self.nodes[index].n_visits += 1;
+            self.nodes[index].reward += reward;
+        }
+    }
+
+    /// Get card from `legal_cards` most often visited at the root.
+    fn most_visited(&self, legal_cards: &[Card]) -> Card {
+        let children = &self.nodes[0].children;
+        children
+            .iter()
+            .filter(|(x, _)| legal_cards.iter().any(|y| y.is_equal_ignore_wild_color(x)))
+            .max_by_key(|(_, child)| self.nodes[*child].n_visits)
+            .map(|(card, _)| *card)
+            .unwrap_or(legal_cards[0])
+    }
+}
+
+/// Strategy of the searching player within simulations, selecting cards in the tree
+/// until leaving it and then saving wild cards.
+struct TreeStrategy {
+    tree: Rc<RefCell<Tree>>,
+}
+
+impl Strategy for TreeStrategy {
+    fn select_card(&self, legal_cards: Cards) -> Play {
+        let hand = legal_cards.clone();
+        let legal_cards = remove_wild_colors(legal_cards);
+        match self.tree.borrow_mut().select(&legal_cards) {
+            Some(card) => Some(with_color(card, &hand)),
+            None => SaveWildsStrategy {}.select_card(legal_cards),
+        }
+    }
+
+    fn select_card_with_view(&self, legal_cards: Cards, view: &PlayerView) -> Play {
+        let legal_cards = remove_wild_colors(legal_cards);
+        match self.tree.borrow_mut().select(&legal_cards) {
+            Some(card) => Some(with_color(card, &view.hand)),
+            None => SaveWildsStrategy {}.select_card_with_view(legal_cards, view),
+        }
+    }
+
+    fn select_color(&self, hand: &Cards, side: Side) -> Color {
+        most_held_color(hand, side)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*; // bring private functions into scope
+    use crate::{HandInfo, Symbol};
+
+    #[test]
+    fn test_tree_expands_then_follows_ucb() {
+        let cards = vec![
+            Card::new(Symbol::Number(1), Some(Color::Red)),
+            Card::new(Symbol::Number(2), Some(Color::Red)),
+        ];
+        let mut tree = Tree::new(EXPLORATION);
+        for reward in [1.0, 0.0] {
+            tree.start();
+            tree.select(&cards).unwrap();
+            assert!(tree.select(&cards).is_none());
+            tree.update(reward);
+        }
+        assert_eq!(tree.nodes.len(), 3);
+        assert_eq!(tree.nodes[0].n_visits, 2);
+
+        // winning card is exploited
+        let winner = tree.nodes[0]
+            .children
+            .iter()
+            .find(|(_, x)| tree.nodes[*x].reward > 0.0)
+            .map(|(x, _)| *x);
+        tree.start();
+        assert_eq!(tree.select(&cards), winner);
+    }
+
+    #[test]
+    fn test_deal_known_cards_and_voids() {
+        let red = Card::new(Symbol::Number(5), Some(Color::Red));
+        let blue = Card::new(Symbol::Number(5), Some(Color::Blue));
+        let mut hand_infos = vec![HandInfo::default(); 3];
+        hand_infos[1].voids = vec![Color::Red];
+        hand_infos[2].known_cards = vec![red];
+        let view = PlayerView {
+            index: 0,
+            hand: vec![],
+            next: 1,
+            hand_sizes: vec![0, 3, 2],
+            teams: None,
+            top_card: red,
+            pile: vec![red],
+            unknown_cards: vec![red, red, red, blue, blue, blue],
+            direction: Direction::Forward,
+            pending_draw: 0,
+            hand_infos,
+        };
+        for _ in 0..10 {
+            let (hands, deck) = deal(&view);
+            assert_eq!(hands[1], vec![blue; 3]);
+            assert!(hands[2].contains(&red));
+            assert_eq!(hands[2].len() + deck.len(), 3);
+        }
+    }
+
+    #[test]
+    fn test_take_drawn_cards() {
+        let red = Card::new(Symbol::Number(5), Some(Color::Red));
+        let blue = Card::new(Symbol::Number(5), Some(Color::Blue));
+        let view = PlayerView {
+            index: 0,
+            hand: vec![blue],
+            next: 1,
+            hand_sizes: vec![1, 1],
+            teams: None,
+            top_card: red,
+            pile: vec![red],
+            unknown_cards: vec![red, blue, blue],
+            direction: Direction::Forward,
+            pending_draw: 0,
+            hand_infos: vec![HandInfo::default(); 2],
+        };
+        let view = take_drawn_cards(&view, &[red]);
+        assert_eq!(view.hand, vec![blue, red]);
+        assert_eq!(view.hand_sizes, vec![2, 1]);
+        assert_eq!(view.unknown_cards, vec![blue, blue]);
+    }
+
+    #[test]
+    fn test_ismcts_strategy_plays_winning_card() {
+        // playing the skip wins in a two-player game, playing the 5 lets the opponent
+        // play their last card
+        let hand = vec![
+            Card::new(Symbol::Skip, Some(Color::Red)),
+            Card::new(Symbol::Number(5), Some(Color::Red)),
+        ];
+        let view = PlayerView {
+            index: 0,
+            hand: hand.clone(),
+            next: 1,
+            hand_sizes: vec![2, 1],
+            teams: None,
+            top_card: Card::new(Symbol::Number(1), Some(Color::Red)),
+            pile: vec![Card::new(Symbol::Number(1), Some(Color::Red))],
+            unknown_cards: vec![Card::new(Symbol::Number(7), Some(Color::Red)); 20],
+            direction: Direction::Forward,
+            pending_draw: 0,
+            hand_infos: vec![HandInfo::default(); 2],
+        };
+        let strategy = IsmctsStrategy::new(Rules::official()).n_iterations(50);
+        let card = strategy.select_card_with_view(hand.clone(), &view);
+        assert_eq!(card, Some(hand[0]));
+    }
+}
@@ -1,196 +1,1114 @@
+// print game progress, unless disabled on the current thread, e.g. for simulations
+macro_rules! log {
+    ($($arg:tt)*) => {
+        if crate::is_verbose() {
+            println!($($arg)*);
+        }
+    };
+}
+
+mod bot;
 mod cycle;
+mod deck;
+mod draw;
+mod env;
+mod greedy;
+mod ismcts;
+#[cfg(feature = "python")]
+mod python;
+mod rating;
+mod rules;
+mod server;
+mod solver;
+mod stats;
+mod tournament;
+mod web;
+pub use bot::ProcessStrategy;
 use colored::ColoredString;
 use colored::Colorize;
-use cycle::Cycle;
+pub use cycle::{Cycle, Direction};
+pub use deck::DeckSpec;
+pub use draw::{DeckDraw, DrawSource, Launcher};
+pub use env::{encode_view, Action, UnoEnv, N_ACTIONS, OBSERVATION_SIZE};
+pub use greedy::{
+    DumpActionsStrategy, HighestPointsStrategy, MostHeldColorStrategy, SaveWildsStrategy,
+};
+pub use ismcts::IsmctsStrategy;
 use itertools::Itertools;
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
+use rand::Rng;
+use rand::SeedableRng;
+pub use rating::{fmt_ratings, Rating, Ratings};
+pub use rules::{DrawMode, FirstCardRule, Rules, RulesError, Stacking, WildDraw4Rule, PRESETS};
+use serde::{Serialize, Serializer};
+pub use server::Server;
+pub use solver::{Solver, SolverStrategy};
+pub use stats::{fmt_stats, GameStats, PlayerStats};
+use std::cell::Cell;
+use std::cmp::Reverse;
 use std::collections::VecDeque;
 use std::fmt;
 use std::str;
+use std::sync::Arc;
 use std::vec::Vec;
-use strum::IntoEnumIterator; // import trait created by EnumIter macro into scope
 use strum_macros::EnumIter;
+pub use tournament::{fmt_standings, Pairing, Standing, Tournament};
 
-const N_CARDS: usize = 108; // number of cards in standard deck
-const N_INITIAL_CARDS: usize = 7; // number of cards in initial player hands
-const N_PLAYERS: usize = 4;
+const ANALYSIS_CARDS: usize = 2; // number of cards in a hand from which to analyze a game
+const PLAYER_NAMES: [&str; 10] = ["A", "B", "C", "D", "E", "F", "G", "H", "I", "J"];
+
+/// Maximum number of players in a game.
+pub const MAX_PLAYERS: usize = PLAYER_NAMES.len();
+
+thread_local! {
+    static VERBOSE: Cell<bool> = const { Cell::new(true) };
+}
+
+/// Enable or disable printing game progress on the current thread.
+pub fn set_verbose(verbose: bool) {
+    VERBOSE.with(|x| x.set(verbose));
+}
+
+/// Check if game progress is printed on the current thread.
+pub fn is_verbose() -> bool {
+    VERBOSE.with(|x| x.get())
+}
 
 // TODO add proper logging
-pub fn run() {
-    // initialize player cycle
-    let n_players = 4;
-    let mut players = PlayerCycle::new(n_players);
-    println!("Players: {:?}", players.get_names());
-
-    // initialize dealer and player hands
-    let mut dealer = Dealer::new();
-    let hands = dealer.draw_hands(n_players, N_INITIAL_CARDS);
-    players.take_hands(hands);
-
-    // initialize pile
-    dealer.flip_first_card();
-
-    // set first card so that actions will be executed at the start of the game
-    let mut play = Some(dealer.top_card());
-
-    // cycle through players until game over
-    loop {
-        // if action card was played, execute card action
-        println!("Played: {}", fmt_play(&play));
-        if let Some(card) = play {
-            match card.symbol {
-                Symbol::Skip => players.skip(),
-                Symbol::Reverse => players.reverse(),
-                Symbol::Draw2 => {
-                    let player = players.next();
-                    let cards = dealer.draw(2);
-                    player.take_cards(cards);
-                    println!("Player: {} takes 2 cards", player.name);
-                }
-                Symbol::WildDraw4 => {
-                    let player = players.next();
-                    let cards = dealer.draw(4);
-                    player.take_cards(cards);
-                    println!("Player: {} takes 4 cards", player.name);
-                }
-                _ => {}
+pub fn run(rules: Rules, n_players: usize) {
+    let mut game = Match::new(n_players, rules);
+    game.play();
+}
+
+/// Play game of `n_players` with `rules` and `seed` silently until a player is close to
+/// going out in a position the [`Solver`] supports, then print all hands and the win
+/// probability of each legal play of the next player. With `max_depth`, larger positions
+/// are searched up to that depth and the win probabilities are estimated.
+pub fn analyze(rules: Rules, n_players: usize, seed: u64, max_depth: Option<usize>) {
+    if !Solver::supports(&rules) {
+        println!("Rules not supported by the solver");
+        return;
+    }
+    let verbose = is_verbose();
+    set_verbose(false);
+    let mut solver = Solver::new(rules.clone());
+    if let Some(max_depth) = max_depth {
+        solver = solver.max_depth(max_depth);
+    }
+    let mut game = Game::with_seed(n_players, n_players - 1, rules, seed);
+    for index in 0..n_players {
+        game.set_strategy(index, Box::new(SaveWildsStrategy {}));
+    }
+    let mut is_endgame = false;
+    let analysis = loop {
+        if let Some(result) = game.step() {
+            break Err(result);
+        }
+        let hand_sizes = game.players.hand_sizes();
+        if hand_sizes.iter().any(|&x| x <= ANALYSIS_CARDS) {
+            is_endgame = true;
+            if let Some(values) = game.analyze(&mut solver) {
+                break Ok(values);
             }
         }
+    };
+    set_verbose(verbose);
+
+    match analysis {
+        Ok(mut values) => {
+            for player in &game.players.players {
+                println!("Player: {} {:?}", player.name, player.hand);
+            }
+            println!("Top card: {}", fmt_card(&game.top_card()));
+            let index = game.players.cycle.peek_next();
+            let name = &game.players.players[index].name;
+            println!("Next player: {}", name);
+            if values.is_empty() {
+                println!("No legal play: {} has to draw", name);
+                return;
+            }
+            match solver.is_exact() {
+                true => println!("Win probabilities:"),
+                false => println!("Estimated win probabilities:"),
+            }
+            values.sort_by(|a, b| b.1.total_cmp(&a.1));
+            for (card, value) in values {
+                println!("{}: {:.1}%", fmt_card(&card), 100.0 * value);
+            }
+        }
+        // positions with pending penalties, unsupported cards or too many cards for an
+        // exact search are not analyzed
+        Err(result) if is_endgame => {
+            let name = &game.players.players[result.winner].name;
+            match solver.is_exact() {
+                true => println!(
+                    "Player: {} won before a position small enough to solve",
+                    name
+                ),
+                false => println!(
+                    "Player: {} won before a position supported by the solver",
+                    name
+                ),
+            }
+        }
+        Err(result) => {
+            let name = &game.players.players[result.winner].name;
+            println!("Player: {} won before the endgame", name);
+        }
+    }
+}
+
+/// A match of multiple games, played until one player reaches the target score.
+pub struct Match {
+    n_players: usize,
+    rules: Rules,
+    scores: Vec<u32>,
+    dealer: usize,
+}
+
+impl Match {
+    /// Create match for `n_players`, played with `rules` until one player reaches the
+    /// target score.
+    pub fn new(n_players: usize, rules: Rules) -> Self {
+        Self {
+            n_players,
+            rules,
+            scores: vec![0; n_players],
+            dealer: n_players - 1, // so that the first player starts the first game
+        }
+    }
+
+    /// Get scores of all players.
+    pub fn scores(&self) -> &[u32] {
+        &self.scores
+    }
+
+    /// Play games until one player reaches the target score, rotating the dealer
+    /// between games, and return the index of the winning player. In partnership mode,
+    /// both partners score the points of a game.
+    pub fn play(&mut self) -> usize {
+        loop {
+            let mut game = Game::new(self.n_players, self.dealer, self.rules.clone());
+            let result = game.play();
+            for index in game.players.team_members(result.winner) {
+                self.scores[index] += result.points;
+            }
+            let stats = GameStats::from_events(self.n_players, game.events());
+            log!("{}", fmt_stats(&stats, &game.players.get_names()));
+            log!("Scores: {:?}", self.scores);
+
+            if self.scores[result.winner] >= self.rules.target_score {
+                let name = game.players.get_names()[result.winner];
+                log!("Player: {} won the match!", name);
+                break result.winner;
+            }
+            self.dealer = (self.dealer + 1) % self.n_players;
+        }
+    }
+}
+
+/// Result of a single game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameResult {
+    /// Index of the player who emptied their hand.
+    pub winner: usize,
+    /// Team of the winner in partnership mode.
+    pub team: Option<usize>,
+    /// Points scored by the winner from the cards left in the opponents' hands.
+    pub points: u32,
+    /// Indexes of players from first to last place, where players left in the game are
+    /// placed by number of cards and eliminated players in reverse order of elimination.
+    pub placements: Vec<usize>,
+}
+
+/// Events emitted during a game.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+    /// Player called "Uno" after playing their second-to-last card.
+    UnoCalled { player: usize },
+    /// Player forgot to call "Uno", was caught by another player and drew penalty cards.
+    UnoCaught {
+        player: usize,
+        by: usize,
+        n_cards: usize,
+    },
+    /// Player started their turn holding `n_cards`.
+    TurnStarted { player: usize, n_cards: usize },
+    /// Player played a card, from their turn, stacking onto a draw card or jumping in.
+    CardPlayed {
+        player: usize,
+        #[serde(serialize_with = "serialize_card")]
+        card: Card,
+    },
+    /// Player drew cards on their turn for lack of a card to play.
+    CardsDrawn { player: usize, n_cards: usize },
+    /// Player drew penalty cards for the given `cause`.
+    PenaltyDrawn {
+        player: usize,
+        n_cards: usize,
+        cause: PenaltyCause,
+    },
+    /// Player was skipped.
+    Skipped { player: usize },
+    /// Player played a card of another color than the top card.
+    ColorChanged { player: usize, color: Color },
+    /// Discard pile except the top card was shuffled into the empty deck.
+    DeckRecycled,
+}
+
+/// Cause of drawing penalty cards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PenaltyCause {
+    /// Player was hit by a draw card, including after losing a wild draw 4 challenge.
+    DrawCard,
+    /// Player spun the color roulette.
+    Roulette,
+    /// Player was caught bluffing by a wild draw 4 challenge.
+    Challenge,
+}
+
+// serialize card of an event as protocol token, e.g. "7:Red"
+fn serialize_card<S: Serializer>(card: &Card, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&bot::fmt_token(card))
+}
+
+/// View of the game available to a player when selecting a card.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlayerView {
+    /// Index of the player.
+    pub index: usize,
+    /// Cards in the player's hand.
+    pub hand: Cards,
+    /// Index of the next player in the current direction of play.
+    pub next: usize,
+    /// Number of cards in each player's hand.
+    pub hand_sizes: Vec<usize>,
+    /// Team of each player in partnership mode.
+    pub teams: Option<Vec<usize>>,
+    /// Top card of the pile.
+    pub top_card: Card,
+    /// Cards on the pile, with the top card last.
+    pub pile: Cards,
+    /// Cards in the deck and the other players' hands in random order.
+    pub unknown_cards: Cards,
+    /// Direction of play.
+    pub direction: Direction,
+    /// Number of cards the player has to draw unless stacking another draw card.
+    pub pending_draw: usize,
+    /// What the player knows about each player's hand from the history of the game.
+    pub hand_infos: Vec<HandInfo>,
+}
+
+/// What a player knows about the hand of another player from the history of the game.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HandInfo {
+    /// Colors of which the other player held no card when drawing for lack of a card to
+    /// play, except for the cards taken since.
+    pub voids: Vec<Color>,
+    /// Number of cards taken since drawing for lack of a card to play, of any color.
+    pub n_free: usize,
+    /// Cards the other player is known to hold, after receiving them from the player or
+    /// showing their hand in a wild draw 4 challenge.
+    pub known_cards: Cards,
+}
+
+impl PlayerView {
+    /// Check if player at `other` is a partner of the player.
+    pub fn is_partner(&self, other: usize) -> bool {
+        match &self.teams {
+            Some(teams) => other != self.index && teams[other] == teams[self.index],
+            None => false,
+        }
+    }
+
+    /// Check if player at `other` is an opponent of the player.
+    pub fn is_opponent(&self, other: usize) -> bool {
+        other != self.index && !self.is_partner(other)
+    }
+}
+
+/// A single game, containing the players, the dealer and the events played so far.
+#[derive(Clone)]
+pub struct Game {
+    players: PlayerCycle,
+    dealer: Dealer,
+    rules: Rules,
+    first: usize,
+    pending_draw: usize,
+    draw_source: Arc<dyn DrawSource>,
+    pending_draw_color: Option<Color>,
+    pending_roulette: bool,
+    wild_draw_4_bluff: Option<(usize, bool)>,
+    events: Vec<Event>,
+}
+
+impl Game {
+    /// Create game for `n_players` with `rules`, where the player after the `dealer`
+    /// starts.
+    pub fn new(n_players: usize, dealer: usize, rules: Rules) -> Self {
+        Self::with_seed(n_players, dealer, rules, rand::random())
+    }
+
+    /// Create game like [`Game::new`], shuffling and drawing cards with a random number
+    /// generator seeded with `seed`.
+    pub fn with_seed(n_players: usize, dealer: usize, rules: Rules, seed: u64) -> Self {
+        // initialize player cycle
+        let first = (dealer + 1) % n_players;
+        let mut players = PlayerCycle::new(n_players, first);
+        if rules.teams {
+            players.form_teams();
+        }
+        log!("Players: {:?}", players.get_names());
+
+        // initialize dealer and player hands
+        let mut dealer = Dealer::with_seed(&rules.deck, seed);
+        let hands = dealer.draw_hands(n_players, rules.n_initial_cards);
+        players.take_hands(hands);
+
+        Self {
+            players,
+            dealer,
+            draw_source: Arc::from(rules.draw_source()),
+            rules,
+            first,
+            pending_draw: 0,
+            pending_draw_color: None,
+            pending_roulette: false,
+            wild_draw_4_bluff: None,
+            events: vec![],
+        }
+    }
+
+    /// Create game from given state with `dealer` and player `hands`, where the player
+    /// at index `first` plays next. If the pile is empty, the first card is flipped when
+    /// the game starts, otherwise the game continues from the top card.
+    pub fn from_state(dealer: Dealer, hands: Vec<Cards>, first: usize, rules: Rules) -> Self {
+        let n_players = hands.len();
+        let mut players = PlayerCycle::new(n_players, first);
+        if rules.teams {
+            players.form_teams();
+        }
+        for (player, hand) in players.players.iter_mut().zip(hands) {
+            player.hand = hand;
+        }
+        if let Some(card) = dealer.pile.last() {
+            assert!(card.color.is_some(), "top card must have a color");
+        }
+
+        Self {
+            players,
+            dealer,
+            draw_source: Arc::from(rules.draw_source()),
+            rules,
+            first,
+            pending_draw: 0,
+            pending_draw_color: None,
+            pending_roulette: false,
+            wild_draw_4_bluff: None,
+            events: vec![],
+        }
+    }
+
+    /// Set `strategy` of player at `index`.
+    pub fn set_strategy(&mut self, index: usize, strategy: Box<dyn Strategy>) {
+        self.players.players[index].strategy = Arc::from(strategy);
+    }
+
+    /// Set `draw_source` used for draw actions, overriding the one given by the rules.
+    pub fn set_draw_source(&mut self, draw_source: Box<dyn DrawSource>) {
+        self.draw_source = Arc::from(draw_source);
+    }
+
+    /// Get hand of player at `index`.
+    pub fn hand(&self, index: usize) -> &[Card] {
+        &self.players.players[index].hand
+    }
+
+    /// Get top card from pile.
+    pub fn top_card(&self) -> Card {
+        self.dealer.top_card()
+    }
+
+    /// Get events emitted so far.
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+
+    /// Compute the win probability of each legal play of the next player with `solver`,
+    /// see [`Solver::analyze`]. Return `None` before the game has started, while a draw
+    /// action is pending or if the solver does not support the position.
+    pub fn analyze(&self, solver: &mut Solver) -> Option<Vec<(Card, f64)>> {
+        if self.dealer.pile.is_empty()
+            || self.pending_draw > 0
+            || self.pending_draw_color.is_some()
+            || self.pending_roulette
+        {
+            return None;
+        }
+        let hands = self
+            .players
+            .players
+            .iter()
+            .map(|x| x.hand.clone())
+            .collect();
+        let deck = self.dealer.deck.iter().copied().collect();
+        let index = self.players.cycle.peek_next();
+        let direction = self.players.cycle.direction();
+        solver.analyze(hands, deck, self.top_card(), index, direction)
+    }
+
+    /// Play game until one player has an empty hand.
+    pub fn play(&mut self) -> GameResult {
+        // cycle through players until game over
+        loop {
+            if let Some(result) = self.step() {
+                break result;
+            }
+        }
+    }
+
+    /// Play turn of next player, starting the game first if the pile is empty, and
+    /// return the result if the game is over.
+    pub fn step(&mut self) -> Option<GameResult> {
+        if self.dealer.pile.is_empty() {
+            self.start();
+        }
+        let n_recycles = self.dealer.n_recycles;
 
         // pick next player
-        let player = players.next();
+        let index = self.players.next_index();
+        self.start_turn(index);
+
+        // if a draw card was played, respond to it, otherwise take normal turn
+        let result = if let Some(color) = self.pending_draw_color.take() {
+            self.draw_until_color(index, color, PenaltyCause::DrawCard);
+            None
+        } else if self.pending_roulette {
+            self.pending_roulette = false;
+            self.spin_roulette(index);
+            None
+        } else {
+            let play = match self.pending_draw {
+                0 => self.take_turn(index),
+                _ => self.respond_to_draw(index),
+            };
 
-        // try playing card from hand
-        let top_card = dealer.top_card();
-        play = player.play_from_hand(&top_card);
-        println!("Played from hand: {}", fmt_play(&play));
-
-        // if no card is played, draw a new card and try playing it
-        if play.is_none() {
-            let new_card = dealer.draw(1);
-            println!("Drawn: {}", fmt_card(&new_card[0]));
-            play = player.play_from_cards(&top_card, new_card.clone());
-            println!("Played from cards: {}", fmt_play(&play));
-            // if the new card is not played, take it onto the hand
-            if play.is_none() {
-                player.take_cards(new_card)
-            }
-        }
-
-        // if card, discard and check game over
-        if let Some(card) = play {
-            dealer.discard(card);
-            if game_over(player) {
-                println!("Player: {} won! Game over.", player.name);
+            // if card, discard and check game over
+            play.and_then(|card| self.play_card(index, card))
+        };
+        self.record_recycles(n_recycles);
+
+        // players reaching the mercy limit are eliminated
+        result.or_else(|| self.eliminate_players())
+    }
+
+    /// Start turn of player at `index`.
+    fn start_turn(&mut self, index: usize) {
+        let n_cards = self.players.players[index].hand.len();
+        self.events.push(Event::TurnStarted {
+            player: index,
+            n_cards,
+        });
+    }
+
+    /// Record recycles of the deck since it was recycled `n_recycles` times.
+    fn record_recycles(&mut self, n_recycles: usize) {
+        for _ in n_recycles..self.dealer.n_recycles {
+            self.events.push(Event::DeckRecycled);
+        }
+    }
+
+    /// Eliminate players holding at least as many cards as the mercy limit, shuffling
+    /// their cards into the deck, and return the result if only one player is left.
+    /// Players holding the most cards are eliminated first, so that the player or team
+    /// holding the fewest cards wins if all of them reach the limit at once.
+    fn eliminate_players(&mut self) -> Option<GameResult> {
+        let limit = self.rules.mercy_limit?;
+        let mut indexes: Vec<usize> = (0..self.players.players.len())
+            .filter(|&x| !self.players.is_eliminated(x))
+            .filter(|&x| self.players.players[x].hand.len() >= limit)
+            .collect();
+        indexes.sort_by_key(|&x| Reverse(self.players.players[x].hand.len()));
+        for index in indexes {
+            if self.players.n_active_teams() == 1 {
+                break;
+            }
+            let cards = self.players.eliminate(index);
+            self.dealer.refill_deck(cards);
+            self.dealer.shuffle_deck();
+        }
+        match self.players.n_active_teams() {
+            1 => Some(self.players.last_player_result()),
+            _ => None,
+        }
+    }
+
+    /// Flip first card onto pile and execute its action.
+    fn start(&mut self) {
+        if self.rules.teams && self.rules.partner_pass {
+            self.players.pass_to_partners();
+        }
+
+        // initialize pile
+        self.dealer.flip_first_card(self.rules.first_card);
+
+        // if a wild card was flipped, the first player chooses its color
+        self.choose_top_color(self.first);
+
+        // execute action of first card at the start of the game
+        let top_card = self.dealer.top_card();
+        log!("Played: {}", fmt_card(&top_card));
+        self.execute_action(self.first, top_card);
+    }
+
+    /// Let player at `index` choose color of top card, if it is a wild card without color.
+    fn choose_top_color(&mut self, index: usize) {
+        let top_card = self.dealer.top_card();
+        if top_card.color.is_none() {
+            let player = &self.players.players[index];
+            let color = player.strategy.select_color(&player.hand, top_card.side);
+            self.dealer.set_top_color(color);
+        }
+    }
+
+    /// Get view of the game for player at `index`.
+    fn view(&self, index: usize) -> PlayerView {
+        PlayerView {
+            index,
+            hand: self.players.players[index].hand.clone(),
+            next: self.players.cycle.peek_next(),
+            hand_sizes: self.players.hand_sizes(),
+            teams: self.players.teams(),
+            top_card: self.dealer.top_card(),
+            pile: self.dealer.pile.clone(),
+            unknown_cards: self.unknown_cards(index),
+            direction: self.players.cycle.direction(),
+            pending_draw: self.pending_draw,
+            hand_infos: self.players.hand_infos(index),
+        }
+    }
+
+    /// Get cards unknown to player at `index`, i.e. cards in the deck and in the other
+    /// players' hands, shuffled so that their order reveals nothing.
+    fn unknown_cards(&self, index: usize) -> Cards {
+        let mut cards: Cards = self.dealer.deck.iter().copied().collect();
+        for (other, player) in self.players.players.iter().enumerate() {
+            if other != index {
+                cards.extend(player.hand.iter().copied());
+            }
+        }
+        cards.shuffle(&mut rand::thread_rng());
+        cards
+    }
+
+    /// Try playing card from hand of player at `index`, otherwise draw cards according
+    /// to the draw mode and try playing them.
+    fn take_turn(&mut self, index: usize) -> Play {
+        let top_card = self.dealer.top_card();
+        let view = self.view(index);
+        let player = &mut self.players.players[index];
+        let play = player.play_from_hand(&top_card, &self.rules, &view);
+        log!("Played from hand: {}", fmt_play(&play));
+        if play.is_some() {
+            return play;
+        }
+
+        // if no card is played, draw new cards and try playing one of them, where the
+        // turn ends if the launcher fires no cards
+        if self.dealer.n_available() > 0 {
+            player.observe_draw(&top_card);
+        }
+        while self.dealer.n_available() > 0 {
+            let new_cards = self.draw_cards(index);
+            if new_cards.is_empty() {
+                break;
+            }
+            let player = &mut self.players.players[index];
+            let play = player.play_from_cards(&top_card, new_cards.clone(), &self.rules, &view);
+            log!("Played from cards: {}", fmt_play(&play));
+            if let Some(card) = play {
+                // take the other new cards onto the hand
+                let mut new_cards = new_cards;
+                let position = new_cards
+                    .iter()
+                    .position(|x| x.is_equal_ignore_wild_color(&card));
+                new_cards.remove(position.expect("played card not drawn"));
+                player.take_cards(new_cards);
+                return play;
+            }
+
+            // if the new cards are not played, take them onto the hand
+            player.take_cards(new_cards);
+            if self.rules.draw_mode == DrawMode::DrawOne {
+                break;
+            }
+        }
+        None
+    }
+
+    /// Draw cards with a single draw action for player at `index`, without taking them
+    /// onto the hand yet.
+    fn draw_cards(&mut self, index: usize) -> Cards {
+        let cards = self.draw_source.draw(&mut self.dealer, 1);
+        log!("Drawn: {:?}", cards);
+        if !cards.is_empty() {
+            self.events.push(Event::CardsDrawn {
+                player: index,
+                n_cards: cards.len(),
+            });
+        }
+        cards
+    }
+
+    /// Respond to pending draw cards, either by stacking another draw card, challenging
+    /// a wild draw 4 or drawing the penalty cards and losing the turn.
+    fn respond_to_draw(&mut self, index: usize) -> Play {
+        debug_assert!(self.pending_draw > 0);
+        let top_card = self.dealer.top_card();
+        let bluff = self.wild_draw_4_bluff.take();
+        let view = self.view(index);
+        let player = &mut self.players.players[index];
+
+        // try passing the penalty on to the next player
+        let play = player.stack_from_hand(&top_card, self.rules.stacking, &view);
+        if play.is_some() {
+            log!("Stacked: {}", fmt_play(&play));
+            return play;
+        }
+
+        // try challenging wild draw 4
+        if let Some((offender, is_legal)) = bluff {
+            if player.strategy.challenge_wild_draw_4() {
+                if is_legal {
+                    // challenger takes 2 more cards
+                    log!("Player: {} lost wild draw 4 challenge", player.name);
+                    self.pending_draw += 2;
+                } else {
+                    // offender shows their hand, takes penalty instead and challenger
+                    // plays as usual
+                    log!("Player: {} won wild draw 4 challenge", player.name);
+                    self.players.show_hand(offender, index);
+                    self.draw_penalty(offender, PenaltyCause::Challenge);
+                    return self.take_turn(index);
+                }
+            }
+        }
+
+        // take penalty and lose turn
+        self.draw_penalty(index, PenaltyCause::DrawCard);
+        None
+    }
+
+    /// Draw cards for player at `index` until drawing a card of `color`, as penalty for
+    /// `cause`.
+    fn draw_until_color(&mut self, index: usize, color: Color, cause: PenaltyCause) {
+        let player = &mut self.players.players[index];
+        let mut n_cards = 0;
+        while self.dealer.n_available() > 0 {
+            let cards = self.dealer.draw(1);
+            let is_color = cards[0].color == Some(color);
+            player.take_cards(cards);
+            n_cards += 1;
+            if is_color {
                 break;
             }
         }
+        log!("Player: {} takes {} cards", player.name, n_cards);
+        self.events.push(Event::PenaltyDrawn {
+            player: index,
+            n_cards,
+            cause,
+        });
+    }
+
+    /// Let player at `index` choose a color for the color roulette on the pile and draw
+    /// cards until drawing a card of that color.
+    fn spin_roulette(&mut self, index: usize) {
+        let top_card = self.dealer.top_card();
+        let player = &self.players.players[index];
+        let color = player.strategy.select_color(&player.hand, top_card.side);
+        self.dealer.set_top_color(color);
+        self.draw_until_color(index, color, PenaltyCause::Roulette);
+    }
+
+    /// Draw pending penalty cards for player at `index` as penalty for `cause`.
+    fn draw_penalty(&mut self, index: usize, cause: PenaltyCause) {
+        let cards = self.draw_source.draw(&mut self.dealer, self.pending_draw);
+        self.pending_draw = 0;
+        let n_cards = cards.len();
+        let player = &mut self.players.players[index];
+        player.take_cards(cards);
+        log!("Player: {} takes {} cards", player.name, n_cards);
+        self.events.push(Event::PenaltyDrawn {
+            player: index,
+            n_cards,
+            cause,
+        });
+    }
+
+    /// Discard `card` played by player at `index`, then handle game over, "Uno", house
+    /// rules and card actions, returning the result if the game is over.
+    fn play_card(&mut self, index: usize, card: Card) -> Option<GameResult> {
+        debug_assert_eq!(self.players.current(), index);
+        let top_card = self.dealer.top_card();
+        self.events.push(Event::CardPlayed {
+            player: index,
+            card,
+        });
+        if let Some(color) = card.color.filter(|&x| Some(x) != top_card.color) {
+            self.events.push(Event::ColorChanged {
+                player: index,
+                color,
+            });
+        }
+        self.players.forget_card(index, &card);
+        if card.symbol == Symbol::DiscardAll {
+            self.discard_all(index, card);
+        }
+        self.dealer.discard(card);
+
+        let player = &self.players.players[index];
+        if game_over(player) {
+            log!("Player: {} won! Game over.", player.name);
+            return Some(self.players.result());
+        }
+        if player.hand.len() == 1 {
+            if let Some(event) =
+                check_uno(&mut self.players, &mut self.dealer, self.rules.uno_penalty)
+            {
+                self.events.push(event);
+            }
+        }
+
+        // remember whether wild draw card was played legally for challenges
+        let is_challengeable = card.is_wild_draw() && card.draw_amount() > 0;
+        if is_challengeable && self.rules.wild_draw_4 == WildDraw4Rule::Challenge {
+            let hand = &self.players.players[index].hand;
+            let is_legal = !hand.iter().any(|x| x.color == top_card.color);
+            self.wild_draw_4_bluff = Some((index, is_legal));
+        }
+
+        if self.rules.seven_zero {
+            self.execute_seven_zero(index, card);
+        }
+        self.execute_action(index, card);
+
+        // give other players the chance to jump in with an identical card
+        if self.rules.jump_in {
+            if let Some((jumper, card)) = self.find_jump_in(index, card) {
+                self.players.players[jumper].remove_from_hand(&card);
+                self.players.jump_to(jumper);
+                log!("Jumped in: {}", fmt_card(&card));
+                return self.play_card(jumper, card);
+            }
+        }
+        None
+    }
+
+    /// Execute action of `card` played by player at `index`.
+    fn execute_action(&mut self, index: usize, card: Card) {
+        match card.symbol {
+            Symbol::Skip => {
+                let player = self.players.skip();
+                self.events.push(Event::Skipped { player });
+            }
+            Symbol::SkipEveryone => {
+                // skip all other players, so that the same player plays again
+                for _ in 1..self.players.n_active() {
+                    let player = self.players.skip();
+                    self.events.push(Event::Skipped { player });
+                }
+            }
+            Symbol::Reverse => {
+                if let Some(player) = self.players.reverse() {
+                    self.events.push(Event::Skipped { player });
+                }
+            }
+            Symbol::WildReverseDraw4 => {
+                // reverse without skipping, so that the previous player draws, which is
+                // the next player anyway with two players
+                if self.players.n_active() > 2 {
+                    self.players.cycle.reverse();
+                }
+                self.pending_draw += card.draw_amount();
+            }
+            Symbol::WildColorRoulette => self.pending_roulette = true,
+            Symbol::Flip => self.flip(index),
+            Symbol::WildDrawColor => self.pending_draw_color = card.color,
+            _ => self.pending_draw += card.draw_amount(),
+        }
+    }
+
+    /// Discard all other cards with the color of discard all `card` from the hand of
+    /// player at `index`.
+    fn discard_all(&mut self, index: usize, card: Card) {
+        let player = &mut self.players.players[index];
+        let (discarded, kept) = std::mem::take(&mut player.hand)
+            .into_iter()
+            .partition(|x| x.color == card.color);
+        player.hand = kept;
+        player.n_free = player.n_free.min(player.hand.len());
+        let discarded: Cards = discarded;
+        log!("Player: {} discards {} cards", player.name, discarded.len());
+        for card in discarded {
+            self.events.push(Event::CardPlayed {
+                player: index,
+                card,
+            });
+            self.players.forget_card(index, &card);
+            self.dealer.discard(card);
+        }
+    }
+
+    /// Flip all cards on the table, with the player at `index` choosing the color of the
+    /// new top card if it is a wild card.
+    fn flip(&mut self, index: usize) {
+        self.dealer.flip();
+        self.players.flip_hands();
+        log!("Flipped: {}", fmt_card(&self.dealer.top_card()));
+        self.choose_top_color(index);
+    }
+
+    /// Swap hands when a 7 is played by player at `index`, or pass all hands on in the
+    /// direction of play when a 0 is played.
+    fn execute_seven_zero(&mut self, index: usize, card: Card) {
+        match card.symbol {
+            Symbol::Number(7) => {
+                let hand_sizes = self.players.hand_sizes();
+                let player = &self.players.players[index];
+                let other = player.strategy.select_swap_player(&hand_sizes, index);
+                debug_assert_ne!(other, index);
+                debug_assert!(!self.players.is_eliminated(other));
+                self.players.swap_hands(index, other);
+            }
+            Symbol::Number(0) => self.players.pass_hands(),
+            _ => {}
+        }
+    }
+
+    /// Find other player who jumps in with a card identical to `card` played by player at
+    /// `index`, returning the player index and their card.
+    fn find_jump_in(&self, index: usize, card: Card) -> Option<(usize, Card)> {
+        if card.is_wild() {
+            return None;
+        }
+        let n_players = self.players.players.len();
+        (1..n_players)
+            .map(|offset| (index + offset) % n_players)
+            .find_map(|other| {
+                let player = &self.players.players[other];
+                let identical = player.hand.iter().find(|x| x.is_same_face(&card))?;
+                match player.strategy.jump_in(identical) {
+                    true => Some((other, *identical)),
+                    false => None,
+                }
+            })
     }
 }
 
+/// Check if current player with one card left calls "Uno", otherwise give other
+/// players the chance to catch them before the next player acts.
+fn check_uno(players: &mut PlayerCycle, dealer: &mut Dealer, uno_penalty: usize) -> Option<Event> {
+    let index = players.current();
+    let player = &players.players[index];
+    debug_assert_eq!(player.hand.len(), 1);
+    if player.strategy.call_uno() {
+        log!("Player: {} calls Uno!", player.name);
+        return Some(Event::UnoCalled { player: index });
+    }
+
+    // opponents try catching in turn order, partners keep quiet
+    let n_players = players.players.len();
+    let partners = players.team_members(index);
+    let caught_by = (1..n_players)
+        .map(|offset| (index + offset) % n_players)
+        .filter(|&other| !players.is_eliminated(other) && !partners.contains(&other))
+        .find(|&other| players.players[other].strategy.catch_uno());
+
+    let by = caught_by?;
+    let n_cards = uno_penalty.min(dealer.n_available());
+    if n_cards > 0 {
+        let cards = dealer.draw(n_cards);
+        players.players[index].take_cards(cards);
+    }
+    log!(
+        "Player: {} caught not calling Uno by {}, takes {} cards",
+        players.players[index].name,
+        players.players[by].name,
+        n_cards
+    );
+    Some(Event::UnoCaught {
+        player: index,
+        by,
+        n_cards,
+    })
+}
+
 /// Check if `player` has empty hand.
 fn game_over(player: &Player) -> bool {
     player.hand.is_empty()
 }
 
-type Cards = Vec<Card>;
+pub type Cards = Vec<Card>;
 type Players = Vec<Player>;
 type Deck = VecDeque<Card>;
-type Play = Option<Card>;
+pub type Play = Option<Card>;
 
 fn generate_players(n_players: usize) -> Players {
-    // TODO expose number of players as input parameter
-    assert_eq!(n_players, N_PLAYERS);
-    let names = ["A", "B", "C", "D"];
+    assert!(
+        (2..=PLAYER_NAMES.len()).contains(&n_players),
+        "number of players must be between 2 and {}",
+        PLAYER_NAMES.len()
+    );
     let mut players: Players = Vec::with_capacity(n_players);
-    for name in names.iter() {
-        let player = Player::new(name);
+    for name in PLAYER_NAMES[..n_players].iter() {
+        let mut player = Player::new(name);
+        player.known_cards = vec![vec![]; n_players];
         players.push(player);
     }
     players
 }
 
 // EnumIter creates new type with implementation of iter method
-#[derive(Hash, Eq, Debug, Clone, Copy, EnumIter, PartialEq)]
-enum Color {
+#[derive(Hash, Eq, Debug, Clone, Copy, EnumIter, PartialEq, Serialize)]
+pub enum Color {
+    // light side
     Red,
     Blue,
     Green,
     Yellow,
+    // dark side
+    Pink,
+    Teal,
+    Orange,
+    Purple,
 }
 
-fn generate_deck() -> Deck {
-    let numbers: [u8; 19] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 1, 2, 3, 4, 5, 6, 7, 8, 9];
-    let color_symbols: [Symbol; 6] = [
-        Symbol::Draw2,
-        Symbol::Draw2,
-        Symbol::Reverse,
-        Symbol::Reverse,
-        Symbol::Skip,
-        Symbol::Skip,
-    ];
-    let wild_symbols: [Symbol; 8] = [
-        Symbol::Wild,
-        Symbol::Wild,
-        Symbol::Wild,
-        Symbol::Wild,
-        Symbol::WildDraw4,
-        Symbol::WildDraw4,
-        Symbol::WildDraw4,
-        Symbol::WildDraw4,
-    ];
-
-    let mut cards: Cards = Vec::with_capacity(N_CARDS);
-
-    // color cards
-    for color in Color::iter() {
-        for number in numbers.into_iter() {
-            let card = Card {
-                symbol: Symbol::Number(number),
-                color: Some(color),
-            };
-            cards.push(card);
+/// Side of double-sided cards currently facing up.
+#[derive(Hash, Eq, Debug, Clone, Copy, PartialEq)]
+pub enum Side {
+    Light,
+    Dark,
+}
+
+impl Side {
+    /// Get colors of side.
+    pub fn colors(&self) -> [Color; 4] {
+        match self {
+            Side::Light => [Color::Red, Color::Blue, Color::Green, Color::Yellow],
+            Side::Dark => [Color::Pink, Color::Teal, Color::Orange, Color::Purple],
         }
-        for symbol in color_symbols.into_iter() {
-            let card = Card {
-                symbol,
-                color: Some(color),
-            };
-            cards.push(card);
+    }
+
+    /// Get other side.
+    fn flipped(&self) -> Self {
+        match self {
+            Side::Light => Side::Dark,
+            Side::Dark => Side::Light,
         }
     }
+}
 
-    // wild cards
-    for symbol in wild_symbols.into_iter() {
-        let card = Card {
-            symbol,
-            color: None,
-        };
-        cards.push(card);
+/// Generate shuffled deck with composition `spec`.
+fn generate_deck(spec: &DeckSpec, rng: &mut impl Rng) -> Deck {
+    let mut color_symbols: Vec<Symbol> = Vec::new();
+    for (number, &copies) in spec.numbers.iter().enumerate() {
+        color_symbols.extend(vec![Symbol::Number(number as u8); copies]);
+    }
+    color_symbols.extend(vec![Symbol::Draw2; spec.draw_2s]);
+    color_symbols.extend(vec![Symbol::Reverse; spec.reverses]);
+    color_symbols.extend(vec![Symbol::Skip; spec.skips]);
+
+    color_symbols.extend(vec![Symbol::Draw1; spec.draw_1s]);
+    color_symbols.extend(vec![Symbol::Flip; spec.flips]);
+
+    color_symbols.extend(vec![Symbol::SkipEveryone; spec.skip_everyones]);
+    color_symbols.extend(vec![Symbol::DiscardAll; spec.discard_alls]);
+
+    let mut wild_symbols: Vec<Symbol> = Vec::new();
+    wild_symbols.extend(vec![Symbol::Wild; spec.wilds]);
+    wild_symbols.extend(vec![Symbol::WildDraw4; spec.wild_draw_4s]);
+    wild_symbols.extend(vec![Symbol::WildDraw2; spec.wild_draw_2s]);
+    wild_symbols.extend(vec![Symbol::WildDraw6; spec.wild_draw_6s]);
+    wild_symbols.extend(vec![Symbol::WildDraw10; spec.wild_draw_10s]);
+    wild_symbols.extend(vec![Symbol::WildReverseDraw4; spec.wild_reverse_draw_4s]);
+    wild_symbols.extend(vec![Symbol::WildColorRoulette; spec.wild_color_roulettes]);
+
+    let mut cards: Cards = Vec::with_capacity(spec.n_cards());
+
+    for _ in 0..spec.n_decks {
+        // color cards
+        for &color in Side::Light.colors()[..spec.n_colors].iter() {
+            for &symbol in color_symbols.iter() {
+                cards.push(Card::new(symbol, Some(color)));
+            }
+        }
+
+        // wild cards
+        for &symbol in wild_symbols.iter() {
+            cards.push(Card::new(symbol, None));
+        }
+    }
+
+    // for double-sided decks, add randomly paired dark faces mirroring the light faces
+    if spec.double_sided {
+        let dark_faces: Vec<Face> = cards.iter().map(|x| x.dark_face()).collect();
+        let dark_faces = randomly_shuffle_cards(dark_faces, rng);
+        for (card, face) in cards.iter_mut().zip(dark_faces) {
+            card.back = Some(face);
+        }
     }
 
     // shuffle deck
-    cards = randomly_shuffle_cards(cards);
+    cards = randomly_shuffle_cards(cards, rng);
 
     // return as deque type
     VecDeque::from(cards)
 }
 
-/// Randomly shuffle cards.
-fn randomly_shuffle_cards(mut cards: Cards) -> Cards {
-    let mut rng = rand::thread_rng();
-    cards.shuffle(&mut rng);
-    cards
+/// Randomly shuffle cards or card faces using `rng`.
+fn randomly_shuffle_cards<T>(mut items: Vec<T>, rng: &mut impl Rng) -> Vec<T> {
+    items.shuffle(rng);
+    items
 }
 
 #[derive(Debug, Hash, Copy, Clone, PartialEq, Eq)]
-enum Symbol {
+pub enum Symbol {
     Number(u8),
     Skip,
     Reverse,
     Draw2,
     Wild,
     WildDraw4,
+    // Uno Flip, light side
+    Draw1,
+    Flip,
+    WildDraw2,
+    // Uno Flip, dark side
+    Draw5,
+    SkipEveryone,
+    WildDrawColor,
+    // Show 'em No Mercy
+    DiscardAll,
+    WildDraw6,
+    WildDraw10,
+    WildReverseDraw4,
+    WildColorRoulette,
+}
+
+impl Symbol {
+    /// Get symbol of the dark face mirroring the light face with this symbol.
+    fn dark(&self) -> Self {
+        match self {
+            Symbol::Skip => Symbol::SkipEveryone,
+            Symbol::Draw1 | Symbol::Draw2 => Symbol::Draw5,
+            Symbol::WildDraw2 | Symbol::WildDraw4 => Symbol::WildDrawColor,
+            _ => *self,
+        }
+    }
 }
 
 // add display trait to convert symbol enum values to string using `to_string`
@@ -200,28 +1118,149 @@ impl fmt::Display for Symbol {
     }
 }
 
+/// Face of a double-sided card facing down.
+#[derive(Debug, Hash, Copy, Clone, PartialEq, Eq)]
+pub struct Face {
+    pub symbol: Symbol,
+    pub color: Option<Color>,
+}
+
 // define card object, with optional color field to handle wild cards where
-// color is chosen by player when the card is played
+// color is chosen by player when the card is played, symbol and color always refer
+// to the face currently facing up, for double-sided cards the other face is kept in back
 #[derive(Hash, Copy, Clone, PartialEq, Eq)]
-struct Card {
-    symbol: Symbol,
-    color: Option<Color>,
+pub struct Card {
+    pub symbol: Symbol,
+    pub color: Option<Color>,
+    pub side: Side,
+    pub back: Option<Face>,
 }
 
 impl Card {
+    /// Create single-sided card.
+    pub fn new(symbol: Symbol, color: Option<Color>) -> Self {
+        Self {
+            symbol,
+            color,
+            side: Side::Light,
+            back: None,
+        }
+    }
+
+    /// Create double-sided card with `light` face facing up.
+    pub fn double_sided(light: Face, dark: Face) -> Self {
+        Self {
+            symbol: light.symbol,
+            color: light.color,
+            side: Side::Light,
+            back: Some(dark),
+        }
+    }
+
+    /// Get dark face mirroring the light face of card.
+    fn dark_face(&self) -> Face {
+        debug_assert_eq!(self.side, Side::Light);
+        let color = self.color.map(|color| {
+            let index = Side::Light.colors().iter().position(|&x| x == color);
+            Side::Dark.colors()[index.expect("not a light color")]
+        });
+        Face {
+            symbol: self.symbol.dark(),
+            color,
+        }
+    }
+
+    /// Turn card over, so that the back face is facing up, resetting the color of wild
+    /// cards.
+    fn flip(&mut self) {
+        if let Some(back) = self.back {
+            let color = match self.is_wild() {
+                true => None,
+                false => self.color,
+            };
+            self.back = Some(Face {
+                symbol: self.symbol,
+                color,
+            });
+            self.symbol = back.symbol;
+            self.color = back.color;
+        }
+        self.side = self.side.flipped();
+    }
+
     fn is_wild(&self) -> bool {
-        matches!(self.symbol, Symbol::Wild | Symbol::WildDraw4)
+        matches!(self.symbol, Symbol::Wild | Symbol::WildColorRoulette) || self.is_wild_draw()
+    }
+
+    /// Check if card is a wild draw card, which cannot be played while holding a card
+    /// matching the color of the top card.
+    fn is_wild_draw(&self) -> bool {
+        matches!(
+            self.symbol,
+            Symbol::WildDraw4
+                | Symbol::WildDraw2
+                | Symbol::WildDrawColor
+                | Symbol::WildDraw6
+                | Symbol::WildDraw10
+                | Symbol::WildReverseDraw4
+        )
     }
 
-    fn is_wild_draw_4(&self) -> bool {
-        matches!(self.symbol, Symbol::WildDraw4)
+    /// Get number of cards the next player has to draw.
+    fn draw_amount(&self) -> usize {
+        match self.symbol {
+            Symbol::Draw1 => 1,
+            Symbol::Draw2 | Symbol::WildDraw2 => 2,
+            Symbol::WildDraw4 | Symbol::WildReverseDraw4 => 4,
+            Symbol::Draw5 => 5,
+            Symbol::WildDraw6 => 6,
+            Symbol::WildDraw10 => 10,
+            _ => 0,
+        }
+    }
+
+    /// Check if `self` can be stacked onto draw card `other` according to `stacking`.
+    fn can_stack_onto(&self, other: &Card, stacking: Stacking) -> bool {
+        if self.draw_amount() == 0 || other.draw_amount() == 0 {
+            return false;
+        }
+        match stacking {
+            Stacking::Off => false,
+            Stacking::SameSymbol => self.symbol == other.symbol,
+            Stacking::EqualOrHigher => self.draw_amount() >= other.draw_amount(),
+        }
+    }
+
+    /// Get points scored for card left in hand at the end of a game.
+    fn points(&self) -> u32 {
+        match self.symbol {
+            Symbol::Number(number) => number as u32,
+            Symbol::Draw1 => 10,
+            Symbol::Skip | Symbol::Reverse | Symbol::Draw2 | Symbol::Flip | Symbol::Draw5 => 20,
+            Symbol::SkipEveryone | Symbol::DiscardAll => 30,
+            // wild cards in Uno Flip score less than in the standard game
+            Symbol::Wild if self.back.is_some() => 40,
+            Symbol::Wild | Symbol::WildDraw4 | Symbol::WildDraw2 => 50,
+            Symbol::WildDraw6
+            | Symbol::WildDraw10
+            | Symbol::WildReverseDraw4
+            | Symbol::WildColorRoulette => 50,
+            Symbol::WildDrawColor => 60,
+        }
+    }
+
+    /// Check if face up is identical to face up of `other`.
+    fn is_same_face(&self, other: &Card) -> bool {
+        self.symbol == other.symbol && self.color == other.color
     }
 
     // TODO identify cards better so that we don't need to rely on this function
     fn is_equal_ignore_wild_color(&self, other: &Card) -> bool {
         match self.is_wild() {
-            true => self.symbol == other.symbol,
-            false => self.symbol == other.symbol && self.color == other.color,
+            true => self.symbol == other.symbol && self.back == other.back,
+            false => {
+                self.symbol == other.symbol && self.color == other.color && self.back == other.back
+            }
         }
     }
 }
@@ -246,6 +1285,10 @@ fn fmt_card(card: &Card) -> ColoredString {
             Color::Blue => symbol.blue(),
             Color::Green => symbol.green(),
             Color::Yellow => symbol.yellow(),
+            Color::Pink => symbol.magenta(),
+            Color::Teal => symbol.cyan(),
+            Color::Orange => symbol.truecolor(255, 165, 0),
+            Color::Purple => symbol.purple(),
         },
     }
 }
@@ -257,7 +1300,7 @@ fn fmt_play(play: &Play) -> ColoredString {
     }
 }
 
-fn filter_legal_cards(cards: Cards, top_card: Card) -> Cards {
+fn filter_legal_cards(cards: Cards, top_card: Card, wild_draw_4: WildDraw4Rule) -> Cards {
     debug_assert!(!cards.is_empty());
     debug_assert!(top_card.color.is_some());
 
@@ -267,7 +1310,8 @@ fn filter_legal_cards(cards: Cards, top_card: Card) -> Cards {
     let mut has_color_match = false;
 
     for card in cards {
-        if card.is_wild_draw_4() {
+        // unless restricted, wild draw cards are always legal and handled like wild cards
+        if card.is_wild_draw() && wild_draw_4 == WildDraw4Rule::Restricted {
             wild_draw_4s.push(card);
             continue;
         }
@@ -287,10 +1331,15 @@ fn filter_legal_cards(cards: Cards, top_card: Card) -> Cards {
 }
 
 /// A single player, containing a name, the hand of cards, and a strategy how to play cards.
+#[derive(Clone)]
 struct Player {
     name: &'static str,
     hand: Cards,
-    strategy: Box<dyn Strategy>, // any object implementing the strategy trait
+    strategy: Arc<dyn Strategy>, // any object implementing the strategy trait
+    team: Option<usize>,         // team in partnership mode
+    voids: Vec<Color>,           // colors not held when drawing for lack of a card to play
+    n_free: usize,               // number of cards taken since, of any color
+    known_cards: Vec<Cards>,     // cards known to be held by each other player
 }
 
 impl Player {
@@ -301,118 +1350,540 @@ impl Player {
         Self {
             name,
             hand,
-            strategy: Box::new(strategy),
+            strategy: Arc::new(strategy),
+            team: None,
+            voids: vec![],
+            n_free: 0,
+            known_cards: vec![],
         }
     }
 
     /// Take `cards` into hand.
     fn take_cards(&mut self, cards: Cards) {
-        debug_assert!(!cards.is_empty());
+        self.n_free += cards.len();
         self.hand.extend(cards);
     }
 
-    /// Play card from `playable_cards` if possible for given `top_card`.
-    fn play_from_cards(&self, top_card: &Card, cards: Cards) -> Play {
+    /// Remember that the player draws for lack of a card to play onto `top_card`, so
+    /// that they hold no card of its color, keeping earlier voids only if no cards were
+    /// taken since.
+    fn observe_draw(&mut self, top_card: &Card) {
+        let Some(color) = top_card.color else {
+            return;
+        };
+        if self.n_free > 0 {
+            self.voids.clear();
+        }
+        if !self.voids.contains(&color) {
+            self.voids.push(color);
+        }
+        self.n_free = 0;
+    }
+
+    /// Get points of cards left in hand.
+    fn hand_points(&self) -> u32 {
+        self.hand.iter().map(|x| x.points()).sum()
+    }
+
+    /// Play card from `playable_cards` if possible for given `top_card` and `rules`.
+    fn play_from_cards(
+        &self,
+        top_card: &Card,
+        cards: Cards,
+        rules: &Rules,
+        view: &PlayerView,
+    ) -> Play {
         debug_assert!(!cards.is_empty());
-        let legal_cards = filter_legal_cards(cards, *top_card);
+        let legal_cards = filter_legal_cards(cards, *top_card, rules.wild_draw_4);
         let legal_cards = remove_duplicates(legal_cards);
         match legal_cards.is_empty() {
             true => None,
-            false => self.strategy.select_card(legal_cards),
+            false => self.strategy.select_card_with_view(legal_cards, view),
+        }
+    }
+
+    /// Play card from hand if possible for given `top_card` and `rules`.
+    fn play_from_hand(&mut self, top_card: &Card, rules: &Rules, view: &PlayerView) -> Play {
+        let cards = self.hand.clone();
+        let card = self.play_from_cards(top_card, cards, rules, view);
+
+        // remove card from hand
+        if let Some(card) = card {
+            self.remove_from_hand(&card);
+        }
+
+        card
+    }
+
+    /// Play draw card from hand which can be stacked onto draw card `top_card`, if any.
+    fn stack_from_hand(&mut self, top_card: &Card, stacking: Stacking, view: &PlayerView) -> Play {
+        let stackable_cards: Cards = self
+            .hand
+            .iter()
+            .filter(|x| x.can_stack_onto(top_card, stacking))
+            .copied()
+            .collect();
+        if stackable_cards.is_empty() {
+            return None;
+        }
+        let card = self
+            .strategy
+            .select_card_with_view(remove_duplicates(stackable_cards), view);
+        if let Some(card) = card {
+            self.remove_from_hand(&card);
+        }
+        card
+    }
+
+    /// Remove `card` from hand.
+    fn remove_from_hand(&mut self, card: &Card) {
+        let index = self
+            .hand
+            .iter()
+            .position(|x| x.is_equal_ignore_wild_color(card))
+            .expect("selected card not in hand");
+        self.hand.remove(index);
+        self.n_free = self.n_free.min(self.hand.len());
+    }
+}
+
+fn remove_duplicates(cards: Cards) -> Cards {
+    cards.into_iter().unique().collect()
+}
+
+// define object for multiple players, handling player cycles
+#[derive(Clone)]
+struct PlayerCycle {
+    players: Players,
+    cycle: Cycle,
+    current: usize,
+    eliminated: Vec<usize>, // indexes of eliminated players in order of elimination
+}
+
+impl PlayerCycle {
+    fn new(n_players: usize, first: usize) -> Self {
+        let players = generate_players(n_players);
+        let cycle = Cycle::new(n_players, first);
+        Self {
+            players,
+            cycle,
+            current: 0,
+            eliminated: vec![],
+        }
+    }
+
+    /// Get next player.
+    fn next(&mut self) -> &mut Player {
+        let index = self.cycle.next().expect("no cycle values");
+        self.current = index;
+        let player = self.players.get_mut(index).expect("no players");
+        log!("Turn: {} | Player: {}", self.cycle.turn(), player.name,);
+        player
+    }
+
+    /// Get index of next player.
+    fn next_index(&mut self) -> usize {
+        self.next();
+        self.current
+    }
+
+    /// Continue player cycle from player at `index`, e.g. when jumping in.
+    fn jump_to(&mut self, index: usize) {
+        self.cycle.jump_to(index);
+        self.current = index;
+    }
+
+    /// Reverse player cycle, or skip the next player if there are only two players,
+    /// returning the index of the skipped player.
+    fn reverse(&mut self) -> Option<usize> {
+        // with two players, reversing has no effect on the order, so official rules
+        // treat reverse like skip and the same player goes again
+        if self.n_active() == 2 {
+            log!("Player cycle reversed with two players, acts as skip.");
+            return Some(self.skip());
+        }
+        log!("Player cycle reversed.");
+        self.cycle.reverse();
+        None
+    }
+
+    /// Skip player, returning their index.
+    fn skip(&mut self) -> usize {
+        let player = self.next();
+        log!("Player: {} skipped", player.name);
+        self.current
+    }
+
+    /// Get index of current player.
+    fn current(&self) -> usize {
+        self.current
+    }
+
+    /// Get number of players who have not been eliminated.
+    fn n_active(&self) -> usize {
+        self.cycle.len()
+    }
+
+    /// Check if player at `index` has been eliminated.
+    fn is_eliminated(&self, index: usize) -> bool {
+        self.eliminated.contains(&index)
+    }
+
+    /// Eliminate player at `index`, removing them from the player cycle, and return the
+    /// cards from their hand.
+    fn eliminate(&mut self, index: usize) -> Cards {
+        debug_assert!(!self.is_eliminated(index));
+        self.cycle.remove(index);
+        self.eliminated.push(index);
+        for player in self.players.iter_mut() {
+            player.known_cards[index].clear();
+        }
+        let player = &mut self.players[index];
+        log!(
+            "Player: {} eliminated with {} cards",
+            player.name,
+            player.hand.len()
+        );
+        player.voids.clear();
+        std::mem::take(&mut player.hand)
+    }
+
+    /// Get indexes of players from first to last place with `winner` in first place.
+    fn placements(&self, winner: usize) -> Vec<usize> {
+        // players left in the game are placed by number of cards, eliminated players
+        // are placed behind them with the first eliminated player last
+        let mut others: Vec<usize> = (0..self.players.len())
+            .filter(|&index| index != winner && !self.is_eliminated(index))
+            .collect();
+        others.sort_by_key(|&index| self.players[index].hand.len());
+        let mut placements = vec![winner];
+        placements.extend(others);
+        placements.extend(self.eliminated.iter().rev());
+        placements
+    }
+
+    /// Get player names.
+    fn get_names(&self) -> Vec<&str> {
+        self.players.iter().map(|x| x.name).collect()
+    }
+
+    /// Get result with the current player as winner, scoring the opponents' hands.
+    fn result(&self) -> GameResult {
+        let winner = self.current;
+        debug_assert!(self.players[winner].hand.is_empty());
+        let team_members = self.team_members(winner);
+        let points = (0..self.players.len())
+            .filter(|index| !team_members.contains(index))
+            .map(|index| self.players[index].hand_points())
+            .sum();
+        let placements = self.placements(winner);
+        GameResult {
+            winner,
+            team: self.players[winner].team,
+            points,
+            placements,
+        }
+    }
+
+    /// Get result with the only player or team left as winner, scoring the cards left in
+    /// their hands.
+    fn last_player_result(&self) -> GameResult {
+        debug_assert_eq!(self.n_active_teams(), 1);
+        let active: Vec<usize> = (0..self.players.len())
+            .filter(|&index| !self.is_eliminated(index))
+            .collect();
+        let winner = active[0];
+        let points = active.iter().map(|&x| self.players[x].hand_points()).sum();
+        let placements = self.placements(winner);
+        GameResult {
+            winner,
+            team: self.players[winner].team,
+            points,
+            placements,
+        }
+    }
+
+    /// Assign players sitting opposite each other to the same team.
+    fn form_teams(&mut self) {
+        let n_players = self.players.len();
+        assert!(
+            n_players >= 4 && n_players.is_multiple_of(2),
+            "partnership mode requires an even number of at least 4 players"
+        );
+        let n_teams = n_players / 2;
+        for (index, player) in self.players.iter_mut().enumerate() {
+            player.team = Some(index % n_teams);
+        }
+    }
+
+    /// Get team of each player in partnership mode.
+    fn teams(&self) -> Option<Vec<usize>> {
+        self.players.iter().map(|x| x.team).collect()
+    }
+
+    /// Get indexes of players in the same team as player at `index`, including the
+    /// player, which is only the player itself if not playing in partnership mode.
+    fn team_members(&self, index: usize) -> Vec<usize> {
+        match self.players[index].team {
+            Some(team) => (0..self.players.len())
+                .filter(|&other| self.players[other].team == Some(team))
+                .collect(),
+            None => vec![index],
+        }
+    }
+
+    /// Get number of teams with players who have not been eliminated, counting each
+    /// player as their own team if not playing in partnership mode.
+    fn n_active_teams(&self) -> usize {
+        (0..self.players.len())
+            .filter(|&index| !self.is_eliminated(index))
+            .map(|index| self.players[index].team.unwrap_or(index))
+            .unique()
+            .count()
+    }
+
+    /// Let each player pass one card to their partner.
+    fn pass_to_partners(&mut self) {
+        // select all cards first, so that no player passes on a card they received
+        let passed: Vec<Card> = self
+            .players
+            .iter_mut()
+            .map(|player| {
+                let card = player.strategy.select_pass_card(&player.hand);
+                player.remove_from_hand(&card);
+                card
+            })
+            .collect();
+        for (index, card) in passed.into_iter().enumerate() {
+            let partner = self
+                .team_members(index)
+                .into_iter()
+                .find(|&other| other != index)
+                .expect("no partner");
+            self.players[partner].take_cards(vec![card]);
+            self.players[index].known_cards[partner].push(card);
+        }
+        log!("Players passed cards to their partners.");
+    }
+
+    /// Get number of cards in each player's hand.
+    fn hand_sizes(&self) -> Vec<usize> {
+        self.players.iter().map(|x| x.hand.len()).collect()
+    }
+
+    /// Swap hands of players at `index` and `other`.
+    fn swap_hands(&mut self, index: usize, other: usize) {
+        let hand = std::mem::take(&mut self.players[index].hand);
+        self.players[index].hand = std::mem::replace(&mut self.players[other].hand, hand);
+        self.move_hand_infos(&[index, other], &[other, index]);
+        log!(
+            "Player: {} swapped hands with {}",
+            self.players[index].name,
+            self.players[other].name
+        );
+    }
+
+    /// Pass all hands on to the next player in the direction of play.
+    fn pass_hands(&mut self) {
+        let active: Vec<usize> = (0..self.players.len())
+            .filter(|&index| !self.is_eliminated(index))
+            .collect();
+        let mut hands: Vec<Cards> = active
+            .iter()
+            .map(|&index| std::mem::take(&mut self.players[index].hand))
+            .collect();
+        match self.cycle.direction() {
+            Direction::Reverse => hands.rotate_left(1),
+            Direction::Forward => hands.rotate_right(1),
+        }
+        for (&index, hand) in active.iter().zip(hands) {
+            self.players[index].hand = hand;
+        }
+        let mut receivers = active.clone();
+        match self.cycle.direction() {
+            Direction::Reverse => receivers.rotate_right(1),
+            Direction::Forward => receivers.rotate_left(1),
+        }
+        self.move_hand_infos(&active, &receivers);
+        log!("Players passed hands on.");
+    }
+
+    /// Move what is known about the hands of players at `senders` to the players at
+    /// `receivers` after passing them on, where each sender knows the hand they passed.
+    fn move_hand_infos(&mut self, senders: &[usize], receivers: &[usize]) {
+        let infos: Vec<(Vec<Color>, usize)> = senders
+            .iter()
+            .map(|&x| {
+                let player = &mut self.players[x];
+                (std::mem::take(&mut player.voids), player.n_free)
+            })
+            .collect();
+        for (&receiver, (voids, n_free)) in receivers.iter().zip(infos) {
+            self.players[receiver].voids = voids;
+            self.players[receiver].n_free = n_free;
+        }
+        for player in self.players.iter_mut() {
+            let known_cards: Vec<Cards> = senders
+                .iter()
+                .map(|&x| std::mem::take(&mut player.known_cards[x]))
+                .collect();
+            for (&receiver, cards) in receivers.iter().zip(known_cards) {
+                player.known_cards[receiver] = cards;
+            }
+        }
+        for (&sender, &receiver) in senders.iter().zip(receivers) {
+            let hand = self.players[receiver].hand.clone();
+            self.players[sender].known_cards[receiver] = hand;
+            self.players[sender].known_cards[sender].clear();
+        }
+    }
+
+    /// Show hand of player at `index` to player at `other`.
+    fn show_hand(&mut self, index: usize, other: usize) {
+        let hand = self.players[index].hand.clone();
+        self.players[other].known_cards[index] = hand;
+    }
+
+    /// Forget `card` played by player at `index` among the cards known to be in their hand.
+    fn forget_card(&mut self, index: usize, card: &Card) {
+        for player in self.players.iter_mut() {
+            let known_cards = &mut player.known_cards[index];
+            if let Some(position) = known_cards
+                .iter()
+                .position(|x| x.is_equal_ignore_wild_color(card))
+            {
+                known_cards.remove(position);
+            }
         }
     }
 
-    /// Play card from hand if possible for given `top_card`.
-    fn play_from_hand(&mut self, top_card: &Card) -> Play {
-        let cards = self.hand.clone();
-        let card = self.play_from_cards(top_card, cards);
+    /// Get what player at `index` knows about each player's hand.
+    fn hand_infos(&self, index: usize) -> Vec<HandInfo> {
+        self.players
+            .iter()
+            .zip(&self.players[index].known_cards)
+            .map(|(player, known_cards)| HandInfo {
+                voids: player.voids.clone(),
+                n_free: player.n_free,
+                known_cards: known_cards.clone(),
+            })
+            .collect()
+    }
 
-        // remove card from hand
-        if let Some(card) = card {
-            let index = self
-                .hand
-                .iter()
-                .position(|x| x.is_equal_ignore_wild_color(&card))
-                .expect("selected card not in hand");
-            self.hand.remove(index);
+    /// Flip all cards in players' hands, forgetting voids of colors of the other side.
+    fn flip_hands(&mut self) {
+        for player in self.players.iter_mut() {
+            player.hand.iter_mut().for_each(|x| x.flip());
+            player.voids.clear();
+            for cards in player.known_cards.iter_mut() {
+                cards.iter_mut().for_each(|x| x.flip());
+            }
         }
+    }
 
-        // say "uno" if one card left
-        if card.is_some() && self.hand.len() == 1 {
-            println!("Player {:?}: Uno!", self.name);
+    /// Take `hands`, one for each player.
+    fn take_hands(&mut self, hands: Vec<Cards>) {
+        assert_eq!(self.players.len(), hands.len());
+        for (player, hand) in self.players.iter_mut().zip(hands) {
+            player.take_cards(hand);
         }
-
-        card
     }
 }
 
-fn remove_duplicates(cards: Cards) -> Cards {
-    cards.into_iter().unique().collect()
+/// Names of the built-in strategies, see [`create_strategy`].
+pub const STRATEGIES: [&str; 7] = [
+    "random",
+    "highest-points",
+    "most-held-color",
+    "save-wilds",
+    "dump-actions",
+    "ismcts",
+    "solver",
+];
+
+/// Create built-in strategy by `name` for games played with `rules`, see [`STRATEGIES`],
+/// or external bot given by `bot:<command>`, see [`ProcessStrategy`].
+pub fn create_strategy(name: &str, rules: &Rules) -> Option<Box<dyn Strategy>> {
+    if let Some(command) = name.strip_prefix("bot:") {
+        let strategy = ProcessStrategy::from_command(command, rules)
+            .inspect_err(|error| eprintln!("cannot start bot: {}", error));
+        return Some(Box::new(strategy.ok()?));
+    }
+    match name {
+        "random" => Some(Box::new(RandomStrategy {})),
+        "highest-points" => Some(Box::new(HighestPointsStrategy {})),
+        "most-held-color" => Some(Box::new(MostHeldColorStrategy {})),
+        "save-wilds" => Some(Box::new(SaveWildsStrategy {})),
+        "dump-actions" => Some(Box::new(DumpActionsStrategy {})),
+        "ismcts" => Some(Box::new(IsmctsStrategy::new(rules.clone()))),
+        "solver" => Some(Box::new(SolverStrategy::new(rules.clone()))),
+        _ => None,
+    }
 }
 
-// define object for multiple players, handling player cycles
-struct PlayerCycle {
-    players: Players,
-    cycle: Cycle,
-}
+/// Strategy trait defining method for selecting a card to play.
+pub trait Strategy {
+    /// Select card from `legal_cards`.
+    // TODO pass on play history for enabling strategies to make smarter decisions
+    fn select_card(&self, legal_cards: Cards) -> Play;
 
-impl PlayerCycle {
-    fn new(n_players: usize) -> Self {
-        let players = generate_players(n_players);
-        let cycle = Cycle::new(n_players);
-        Self { players, cycle }
+    /// Select card from `legal_cards` given the `view` of the game, ignoring the view by
+    /// default.
+    fn select_card_with_view(&self, legal_cards: Cards, _view: &PlayerView) -> Play {
+        self.select_card(legal_cards)
     }
 
-    /// Get next player.
-    fn next(&mut self) -> &mut Player {
-        let index = self.cycle.next().expect("no cycle values");
-        let player = self.players.get_mut(index).expect("no players");
-        println!("Turn: {} | Player: {}", self.cycle.turn(), player.name,);
-        player
+    /// Select card from `hand` to pass to the partner in partnership mode, passing the
+    /// first card by default.
+    fn select_pass_card(&self, hand: &Cards) -> Card {
+        hand[0]
     }
 
-    /// Reverse player cycle.
-    fn reverse(&mut self) {
-        println!("Player cycle reversed.");
-        self.cycle.reverse();
+    /// Select color for a wild card on `side` given the current `hand`, selecting a
+    /// random color by default.
+    fn select_color(&self, _hand: &Cards, side: Side) -> Color {
+        select_random_color(side)
     }
 
-    /// Skip player.
-    fn skip(&mut self) {
-        let player = self.next();
-        println!("Player: {} skipped", player.name);
+    /// Select other player to swap hands with when playing a 7, given the `hand_sizes`
+    /// of all players and own `index`, selecting the player with the fewest cards by
+    /// default. Players without cards have been eliminated and cannot be selected.
+    fn select_swap_player(&self, hand_sizes: &[usize], index: usize) -> usize {
+        (0..hand_sizes.len())
+            .filter(|&other| other != index && hand_sizes[other] > 0)
+            .min_by_key(|&other| hand_sizes[other])
+            .expect("no other players")
     }
 
-    /// Get player names.
-    fn get_names(&self) -> Vec<&str> {
-        self.players.iter().map(|x| x.name).collect()
+    /// Decide whether to jump in out of turn with `card`, identical to the top card.
+    fn jump_in(&self, _card: &Card) -> bool {
+        true
     }
 
-    /// Take `hands`, one for each player.
-    fn take_hands(&mut self, hands: Vec<Cards>) {
-        assert_eq!(self.players.len(), hands.len());
-        for (player, hand) in self.players.iter_mut().zip(hands.into_iter()) {
-            player.take_cards(hand);
-        }
+    /// Decide whether to challenge a wild draw 4 played by the previous player.
+    fn challenge_wild_draw_4(&self) -> bool {
+        false
     }
-}
 
-/// Strategy trait defining method for selecting a card to play.
-trait Strategy {
-    /// Select card from `legal_cards`.
-    // TODO pass on play history for enabling strategies to make smarter decisions
-    fn select_card(&self, legal_cards: Cards) -> Play;
+    /// Decide whether to call "Uno" after playing the second-to-last card.
+    fn call_uno(&self) -> bool {
+        true
+    }
+
+    /// Decide whether to catch another player who forgot to call "Uno".
+    fn catch_uno(&self) -> bool {
+        true
+    }
 }
 
-// TODO implement more strategies
 /// Random strategy.
 #[derive(Debug)]
-struct RandomStrategy {}
+pub struct RandomStrategy {}
 
-/// Randomly select color.
-fn select_random_color() -> Color {
+/// Randomly select color of `side`.
+fn select_random_color(side: Side) -> Color {
     let mut rng = rand::thread_rng();
-    let colors: Vec<Color> = Color::iter().collect();
+    let colors = side.colors();
     // de-reference data, see e.g. https://micouy.github.io/rust-dereferencing/
     *colors.choose(&mut rng).expect("empty colors")
 }
@@ -426,34 +1897,86 @@ impl Strategy for RandomStrategy {
         if card.is_wild() {
             // if wild card, select color
             debug_assert!(card.color.is_none());
-            let color = select_random_color();
+            let color = select_random_color(card.side);
             card.color = Some(color);
         }
         Some(card)
     }
+
+    /// Randomly select other player to swap hands with.
+    fn select_swap_player(&self, hand_sizes: &[usize], index: usize) -> usize {
+        let others: Vec<usize> = (0..hand_sizes.len())
+            .filter(|&x| x != index && hand_sizes[x] > 0)
+            .collect();
+        *others
+            .choose(&mut rand::thread_rng())
+            .expect("no other players")
+    }
+
+    /// Randomly decide whether to challenge a wild draw 4.
+    fn challenge_wild_draw_4(&self) -> bool {
+        rand::thread_rng().gen_bool(0.5)
+    }
+
+    /// Randomly decide whether to call "Uno".
+    fn call_uno(&self) -> bool {
+        rand::thread_rng().gen_bool(0.5)
+    }
+
+    /// Randomly decide whether to catch another player.
+    fn catch_uno(&self) -> bool {
+        rand::thread_rng().gen_bool(0.5)
+    }
 }
 
 // define dealer object to handle interactions between deck and pile
-#[derive(Debug)]
-struct Dealer {
+#[derive(Debug, Clone)]
+pub struct Dealer {
     deck: Deck,
     pile: Cards,
+    rng: StdRng,
+    n_recycles: usize, // number of times the pile was recycled into the deck
 }
 
 impl Dealer {
-    fn new() -> Self {
-        let deck = generate_deck();
-        let pile: Cards = Vec::with_capacity(N_CARDS);
-        Self { deck, pile }
+    /// Create dealer with shuffled deck with composition `spec`.
+    pub fn new(spec: &DeckSpec) -> Self {
+        Self::with_seed(spec, rand::random())
+    }
+
+    /// Create dealer like [`Dealer::new`], shuffling with a random number generator
+    /// seeded with `seed`.
+    pub fn with_seed(spec: &DeckSpec, seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let deck = generate_deck(spec, &mut rng);
+        let pile: Cards = Vec::with_capacity(spec.n_cards());
+        Self {
+            deck,
+            pile,
+            rng,
+            n_recycles: 0,
+        }
+    }
+
+    /// Create dealer from `deck` and discard `pile` in given order, where cards are
+    /// drawn from the front of the deck and the last card of the pile is the top card.
+    pub fn from_cards(deck: Cards, pile: Cards) -> Self {
+        let deck = deck.into_iter().rev().collect();
+        let rng = StdRng::from_entropy();
+        Self {
+            deck,
+            pile,
+            rng,
+            n_recycles: 0,
+        }
     }
 
     /// Draw `n_cards` cards from deck.
     fn draw(&mut self, n_cards: usize) -> Cards {
-        let n_pile = self.pile.len();
         let n_available = self.deck.len();
 
         // check there are enough cards in deck and pile
-        debug_assert!(n_cards < (n_pile + n_available - 1));
+        debug_assert!(n_cards <= self.n_available());
 
         if n_cards <= n_available {
             // if enough cards are in the deck, simply draw cards
@@ -471,6 +1994,12 @@ impl Dealer {
         }
     }
 
+    /// Get number of cards available for drawing from deck and pile, keeping the top
+    /// card.
+    fn n_available(&self) -> usize {
+        self.deck.len() + self.pile.len().saturating_sub(1)
+    }
+
     // Draw `n_cards` from deck, without recycling pile.
     fn draw_from_deck(&mut self, n_cards: usize) -> Cards {
         let n_available = self.deck.len();
@@ -490,18 +2019,73 @@ impl Dealer {
         self.pile.push(card);
     }
 
-    /// Flip first card of deck onto pile to start the game, discarding wild cards.
-    fn flip_first_card(&mut self) {
-        // if the card is a wild card, it is returned to the deck and a new card is drawn.
-        let card = loop {
-            // take first element of vector without copy, destroying vector
-            let card = self.draw(1).into_iter().nth(0).expect("no cards drawn");
-            match card.is_wild() {
-                true => self.refill_deck(vec![card]),
-                false => break card,
+    /// Flip first card of deck onto pile to start the game, discarding wild cards
+    /// according to `rule`.
+    fn flip_first_card(&mut self, rule: FirstCardRule) {
+        // cards are only redrawn if the deck holds a card to keep, which it does not if
+        // all number and action cards were dealt, so that the first card is kept as it is
+        let is_redrawn = |card: &Card| match rule {
+            FirstCardRule::Official => {
+                card.is_wild_draw() || card.symbol == Symbol::WildColorRoulette
             }
+            FirstCardRule::Simplified => card.is_wild(),
         };
-        self.discard(card);
+        let can_redraw = self.deck.iter().any(|x| !is_redrawn(x));
+        match rule {
+            FirstCardRule::Official => {
+                // if the card is a wild draw or color roulette card, it is reshuffled into
+                // the deck and a new card is drawn, other wild cards stay on the pile
+                // without color until the first player chooses one
+                let card = loop {
+                    let card = self.draw(1).into_iter().nth(0).expect("no cards drawn");
+                    match can_redraw && is_redrawn(&card) {
+                        true => {
+                            self.refill_deck(vec![card]);
+                            self.shuffle_deck();
+                        }
+                        false => break card,
+                    }
+                };
+                self.pile.push(card);
+            }
+            FirstCardRule::Simplified => {
+                // if the card is a wild card, it is returned to the deck and a new card is
+                // drawn.
+                let card = loop {
+                    // take first element of vector without copy, destroying vector
+                    let card = self.draw(1).into_iter().nth(0).expect("no cards drawn");
+                    match can_redraw && is_redrawn(&card) {
+                        true => self.refill_deck(vec![card]),
+                        false => break card,
+                    }
+                };
+                self.pile.push(card);
+            }
+        }
+    }
+
+    /// Flip all cards in deck and pile, turning both stacks over.
+    fn flip(&mut self) {
+        let deck = self.deck.drain(..).rev().map(|mut x| {
+            x.flip();
+            x
+        });
+        self.deck = deck.collect();
+        self.pile.reverse();
+        self.pile.iter_mut().for_each(|x| x.flip());
+    }
+
+    /// Set `color` of wild top card on pile.
+    fn set_top_color(&mut self, color: Color) {
+        let card = self.pile.last_mut().expect("empty pile");
+        debug_assert!(card.is_wild());
+        card.color = Some(color);
+    }
+
+    /// Randomly shuffle deck.
+    fn shuffle_deck(&mut self) {
+        let cards = self.deck.drain(..).collect();
+        self.deck = VecDeque::from(randomly_shuffle_cards(cards, &mut self.rng));
     }
 
     /// Refill deck with `cards`.
@@ -521,8 +2105,9 @@ impl Dealer {
         debug_assert!(n > 0); // pile must have at least one card
         let end = self.pile.len() - 1; // keep top card
         let mut cards = self.pile.drain(0..end).collect();
-        cards = randomly_shuffle_cards(cards);
+        cards = randomly_shuffle_cards(cards, &mut self.rng);
         self.refill_deck(cards);
+        self.n_recycles += 1;
     }
 
     /// Draw `n_cards` initial hands for `n_players`.
@@ -553,10 +2138,33 @@ mod tests {
     use super::*; // bring private functions into scope
     use rstest::rstest;
 
+    const N_PLAYERS: usize = 4;
+
     #[test]
     fn test_generate_deck_n_cards() {
-        let deck = generate_deck();
-        assert_eq!(deck.len(), N_CARDS);
+        let spec = DeckSpec::standard();
+        let deck = generate_deck(&spec, &mut rand::thread_rng());
+        assert_eq!(deck.len(), spec.n_cards());
+    }
+
+    #[test]
+    fn test_generate_deck_custom_spec() {
+        let spec = DeckSpec::standard()
+            .n_decks(2)
+            .n_colors(2)
+            .number(0, 0)
+            .reverses(0)
+            .wilds(1);
+        let deck = generate_deck(&spec, &mut rand::thread_rng());
+        assert_eq!(deck.len(), spec.n_cards());
+
+        let count = |symbol: Symbol| deck.iter().filter(|x| x.symbol == symbol).count();
+        assert_eq!(count(Symbol::Number(0)), 0);
+        assert_eq!(count(Symbol::Number(5)), 2 * 2 * 2);
+        assert_eq!(count(Symbol::Reverse), 0);
+        assert_eq!(count(Symbol::Wild), 2);
+        assert_eq!(count(Symbol::WildDraw4), 2 * 4);
+        assert_eq!(deck.iter().filter_map(|x| x.color).unique().count(), 2);
     }
 
     // helper function for testing to generate cards
@@ -564,7 +2172,7 @@ mod tests {
         let n = values.len();
         let mut cards = Vec::with_capacity(n);
         for (symbol, color) in values.into_iter() {
-            let card = Card { symbol, color };
+            let card = Card::new(symbol, color);
             cards.push(card);
         }
         cards
@@ -572,11 +2180,11 @@ mod tests {
 
     #[test]
     fn test_dealer_flip_first_card() {
-        let mut dealer = Dealer::new();
+        let mut dealer = Dealer::new(&DeckSpec::standard());
         assert_eq!(dealer.pile.len(), 0);
 
         let n_before = dealer.deck.len();
-        dealer.flip_first_card();
+        dealer.flip_first_card(FirstCardRule::Simplified);
         let n_after = dealer.deck.len();
 
         assert_eq!(dealer.pile.len(), 1);
@@ -584,21 +2192,35 @@ mod tests {
     }
 
     #[test]
-    fn test_dealer_flip_first_card_wild_cards() {
-        let mut dealer = Dealer::new();
+    fn test_dealer_from_cards_draw_order() {
+        let deck = generate_cards(vec![
+            (Symbol::Number(1), Some(Color::Red)),
+            (Symbol::Number(2), Some(Color::Red)),
+            (Symbol::Number(3), Some(Color::Red)),
+        ]);
+        let pile = generate_cards(vec![
+            (Symbol::Number(4), Some(Color::Blue)),
+            (Symbol::Number(5), Some(Color::Blue)),
+        ]);
+        let mut dealer = Dealer::from_cards(deck.clone(), pile.clone());
+        assert_eq!(dealer.top_card(), pile[1]);
+        assert_eq!(dealer.draw(1), deck[..1]);
+        assert_eq!(dealer.draw(1), deck[1..2]);
+    }
 
-        // add wild cards to back of deck
+    #[test]
+    fn test_dealer_flip_first_card_wild_cards() {
+        // put wild cards at front of deck
         let first_cards = generate_cards(vec![
             (Symbol::Number(0), Some(Color::Red)),
             (Symbol::Wild, None),
             (Symbol::WildDraw4, None),
         ]);
-        for card in first_cards.clone() {
-            dealer.deck.push_back(card)
-        }
+        let deck = first_cards.iter().rev().copied().collect();
+        let mut dealer = Dealer::from_cards(deck, vec![]);
 
         assert!(dealer.pile.is_empty());
-        dealer.flip_first_card();
+        dealer.flip_first_card(FirstCardRule::Simplified);
 
         // check top card
         assert!(!dealer.top_card().is_wild());
@@ -609,6 +2231,81 @@ mod tests {
         assert_eq!(dealer.deck[1], first_cards[2]);
     }
 
+    #[rstest]
+    #[case(FirstCardRule::Official)]
+    #[case(FirstCardRule::Simplified)]
+    fn test_dealer_flip_first_card_only_wild_cards(#[case] rule: FirstCardRule) {
+        // without a card to keep, the first card is flipped as it is
+        let deck = generate_cards(vec![(Symbol::WildDraw4, None), (Symbol::WildDraw4, None)]);
+        let mut dealer = Dealer::from_cards(deck, vec![]);
+        dealer.flip_first_card(rule);
+        assert_eq!(dealer.top_card().symbol, Symbol::WildDraw4);
+        assert_eq!(dealer.deck.len(), 1);
+    }
+
+    #[test]
+    fn test_dealer_flip_first_card_wild_official() {
+        let card = generate_cards(vec![(Symbol::Wild, None)])[0];
+        let mut dealer = Dealer::from_cards(vec![card], vec![]);
+
+        dealer.flip_first_card(FirstCardRule::Official);
+
+        // check wild card stays on pile until color is chosen
+        assert_eq!(dealer.top_card(), card);
+        dealer.set_top_color(Color::Green);
+        assert_eq!(dealer.top_card().color, Some(Color::Green));
+    }
+
+    #[test]
+    fn test_dealer_flip_first_card_wild_draw_4_official() {
+        let cards = generate_cards(vec![
+            (Symbol::WildDraw4, None),
+            (Symbol::Number(1), Some(Color::Red)),
+            (Symbol::Number(2), Some(Color::Red)),
+        ]);
+        let mut dealer = Dealer::from_cards(cards.clone(), vec![]);
+        dealer.flip_first_card(FirstCardRule::Official);
+
+        // check wild draw 4 is reshuffled into deck
+        assert!(cards[1..].contains(&dealer.top_card()));
+        assert_eq!(dealer.pile.len(), 1);
+        assert_eq!(dealer.deck.len(), 2);
+        assert!(dealer.deck.contains(&cards[0]));
+    }
+
+    #[test]
+    fn test_player_cycle_reverse_first_turn_dealer_starts() {
+        let dealer = 1;
+        let mut players = PlayerCycle::new(N_PLAYERS, dealer + 1);
+        players.reverse();
+        players.next();
+        assert_eq!(players.current(), dealer);
+        players.next();
+        assert_eq!(players.current(), 0);
+    }
+
+    #[rstest]
+    #[case(2)]
+    #[case(4)]
+    #[case(10)]
+    fn test_generate_players_n_players(#[case] n_players: usize) {
+        let players = generate_players(n_players);
+        assert_eq!(players.len(), n_players);
+        assert_eq!(players.iter().map(|x| x.name).unique().count(), n_players);
+    }
+
+    #[test]
+    fn test_player_cycle_reverse_two_players_acts_as_skip() {
+        let mut players = PlayerCycle::new(2, 0);
+        players.next();
+        assert_eq!(players.current(), 0);
+        players.reverse();
+        players.next();
+        assert_eq!(players.current(), 0);
+        players.next();
+        assert_eq!(players.current(), 1);
+    }
+
     #[rstest]
     #[case(0)]
     #[case(1)]
@@ -616,7 +2313,7 @@ mod tests {
     #[case(7)]
     #[case(13)]
     fn test_dealer_draw_n_cards_without_recycling(#[case] n: usize) {
-        let mut dealer = Dealer::new();
+        let mut dealer = Dealer::new(&DeckSpec::standard());
         let n_before = dealer.deck.len();
         let cards = dealer.draw(n);
         let n_after = dealer.deck.len();
@@ -630,13 +2327,13 @@ mod tests {
         let n = 20;
 
         // draw most cards from deck
-        let mut dealer = Dealer::new();
+        let mut dealer = Dealer::new(&DeckSpec::standard());
         let mut _cards = dealer.draw(100);
 
         // set color for discard to work
         for mut _card in _cards.into_iter() {
             if _card.is_wild() {
-                _card.color = Some(select_random_color());
+                _card.color = Some(select_random_color(Side::Light));
             }
             dealer.discard(_card);
         }
@@ -651,12 +2348,518 @@ mod tests {
         assert_eq!(top_card, dealer.top_card()); // check top card stays the same
     }
 
+    // helper strategy for testing which always calls "Uno" and catches other players
+    struct AttentiveStrategy {}
+
+    impl Strategy for AttentiveStrategy {
+        fn select_card(&self, legal_cards: Cards) -> Play {
+            RandomStrategy {}.select_card(legal_cards)
+        }
+    }
+
+    // helper strategy for testing which never calls "Uno"
+    struct ForgetfulStrategy {}
+
+    impl Strategy for ForgetfulStrategy {
+        fn select_card(&self, legal_cards: Cards) -> Play {
+            RandomStrategy {}.select_card(legal_cards)
+        }
+
+        fn call_uno(&self) -> bool {
+            false
+        }
+    }
+
     #[test]
-    fn test_filter_legal_cards_top_card_red_1() {
-        let top_card = Card {
-            symbol: Symbol::Number(1),
-            color: Some(Color::Red),
+    fn test_check_uno_called() {
+        let mut players = PlayerCycle::new(N_PLAYERS, 0);
+        let mut dealer = Dealer::new(&DeckSpec::standard());
+        let index = players.current();
+        players.players[index].strategy = Arc::new(AttentiveStrategy {});
+        players.players[index].take_cards(dealer.draw(1));
+
+        let event = check_uno(&mut players, &mut dealer, Rules::official().uno_penalty);
+        assert_eq!(event, Some(Event::UnoCalled { player: index }));
+        assert_eq!(players.players[index].hand.len(), 1);
+    }
+
+    #[test]
+    fn test_check_uno_caught() {
+        let mut players = PlayerCycle::new(N_PLAYERS, 0);
+        let mut dealer = Dealer::new(&DeckSpec::standard());
+        let index = players.current();
+        for player in players.players.iter_mut() {
+            player.strategy = Arc::new(ForgetfulStrategy {});
+        }
+        players.players[index].take_cards(dealer.draw(1));
+
+        let uno_penalty = Rules::official().uno_penalty;
+        let event = check_uno(&mut players, &mut dealer, uno_penalty);
+        let expected = Event::UnoCaught {
+            player: index,
+            by: index + 1,
+            n_cards: uno_penalty,
+        };
+        assert_eq!(event, Some(expected));
+        assert_eq!(players.players[index].hand.len(), 1 + uno_penalty);
+    }
+
+    #[test]
+    fn test_check_uno_not_caught_by_partner() {
+        let mut players = PlayerCycle::new(N_PLAYERS, 0);
+        let mut dealer = Dealer::new(&DeckSpec::standard());
+        let index = players.current();
+        // seat the partner of the offender next to them
+        for (other, player) in players.players.iter_mut().enumerate() {
+            player.strategy = Arc::new(AttentiveStrategy {});
+            player.team = Some((N_PLAYERS + other - index) % N_PLAYERS / 2);
+        }
+        players.players[index].strategy = Arc::new(ForgetfulStrategy {});
+        players.players[index].take_cards(dealer.draw(1));
+
+        let uno_penalty = Rules::official().uno_penalty;
+        let event = check_uno(&mut players, &mut dealer, uno_penalty);
+        let expected = Event::UnoCaught {
+            player: index,
+            by: (index + 2) % N_PLAYERS,
+            n_cards: uno_penalty,
+        };
+        assert_eq!(event, Some(expected));
+    }
+
+    #[test]
+    fn test_generate_deck_flip() {
+        let spec = DeckSpec::flip();
+        let mut deck: Cards = generate_deck(&spec, &mut rand::thread_rng())
+            .into_iter()
+            .collect();
+        assert_eq!(deck.len(), spec.n_cards());
+        assert!(deck
+            .iter()
+            .all(|x| x.side == Side::Light && x.back.is_some()));
+
+        deck.iter_mut().for_each(|x| x.flip());
+        let count = |symbol: Symbol| deck.iter().filter(|x| x.symbol == symbol).count();
+        assert_eq!(count(Symbol::SkipEveryone), 8);
+        assert_eq!(count(Symbol::Draw5), 8);
+        assert_eq!(count(Symbol::WildDrawColor), 4);
+        assert!(deck.iter().all(|x| x.side == Side::Dark));
+        assert!(deck
+            .iter()
+            .filter_map(|x| x.color)
+            .all(|x| Side::Dark.colors().contains(&x)));
+    }
+
+    #[test]
+    fn test_card_flip() {
+        let light = Face {
+            symbol: Symbol::Wild,
+            color: None,
+        };
+        let dark = Face {
+            symbol: Symbol::Number(3),
+            color: Some(Color::Teal),
+        };
+        let mut card = Card::double_sided(light, dark);
+        card.color = Some(Color::Red); // wild color chosen when played
+
+        card.flip();
+        assert_eq!(
+            (card.symbol, card.color, card.side),
+            (dark.symbol, dark.color, Side::Dark)
+        );
+
+        card.flip();
+        assert_eq!(card, Card::double_sided(light, dark));
+    }
+
+    #[test]
+    fn test_dealer_flip() {
+        let cards: Cards = (1..=4)
+            .map(|number| {
+                let light = Face {
+                    symbol: Symbol::Number(number),
+                    color: Some(Color::Red),
+                };
+                let dark = Face {
+                    symbol: Symbol::Number(number),
+                    color: Some(Color::Pink),
+                };
+                Card::double_sided(light, dark)
+            })
+            .collect();
+        let mut dealer = Dealer::from_cards(cards[..2].to_vec(), cards[2..].to_vec());
+        dealer.flip();
+
+        // pile is turned over, so that the bottom card is now on top
+        assert_eq!(dealer.top_card().symbol, Symbol::Number(3));
+        assert_eq!(dealer.top_card().color, Some(Color::Pink));
+
+        // deck is turned over, so that the last card is drawn first
+        assert_eq!(dealer.draw(1)[0].symbol, Symbol::Number(2));
+    }
+
+    #[rstest]
+    #[case(Symbol::Number(0), 0)]
+    #[case(Symbol::Number(7), 7)]
+    #[case(Symbol::Skip, 20)]
+    #[case(Symbol::Reverse, 20)]
+    #[case(Symbol::Draw2, 20)]
+    #[case(Symbol::Wild, 50)]
+    #[case(Symbol::WildDraw4, 50)]
+    fn test_card_points(#[case] symbol: Symbol, #[case] expected: u32) {
+        let card = Card::new(symbol, None);
+        assert_eq!(card.points(), expected);
+    }
+
+    #[test]
+    fn test_player_cycle_result() {
+        let mut players = PlayerCycle::new(N_PLAYERS, 0);
+        let hands = vec![
+            vec![],
+            generate_cards(vec![(Symbol::Number(3), Some(Color::Red))]),
+            generate_cards(vec![
+                (Symbol::Skip, Some(Color::Blue)),
+                (Symbol::Wild, None),
+            ]),
+            generate_cards(vec![(Symbol::Number(0), Some(Color::Green))]),
+        ];
+        for (player, hand) in players.players.iter_mut().zip(hands) {
+            player.hand = hand;
+        }
+        players.next();
+
+        let result = players.result();
+        assert_eq!(
+            result,
+            GameResult {
+                winner: 0,
+                team: None,
+                points: 73,
+                placements: vec![0, 1, 3, 2]
+            }
+        );
+    }
+
+    #[test]
+    fn test_match_play_until_target_score() {
+        let target_score = 100;
+        let rules = Rules {
+            target_score,
+            ..Rules::official()
+        };
+        let mut game = Match::new(N_PLAYERS, rules);
+        let winner = game.play();
+        assert!(game.scores()[winner] >= target_score);
+        assert_eq!(
+            game.scores().iter().filter(|&&x| x >= target_score).count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_game_play_two_players() {
+        let mut game = Game::new(2, 0, Rules::official());
+        let result = game.play();
+        assert!(result.winner < 2);
+    }
+
+    #[test]
+    fn test_game_play_greedy_strategies() {
+        let mut game = Game::new(4, 0, Rules::house_classic());
+        game.set_strategy(0, Box::new(HighestPointsStrategy {}));
+        game.set_strategy(1, Box::new(MostHeldColorStrategy {}));
+        game.set_strategy(2, Box::new(SaveWildsStrategy {}));
+        game.set_strategy(3, Box::new(DumpActionsStrategy {}));
+        let result = game.play();
+        assert!(result.winner < 4);
+    }
+
+    #[test]
+    fn test_create_strategy() {
+        for name in STRATEGIES {
+            assert!(
+                create_strategy(name, &Rules::official()).is_some(),
+                "missing strategy: {}",
+                name
+            );
+        }
+        assert!(create_strategy("unknown", &Rules::official()).is_none());
+        assert!(create_strategy(
+            "bot:python3 examples/bots/random_bot.py",
+            &Rules::official()
+        )
+        .is_some());
+        assert!(create_strategy("bot:", &Rules::official()).is_none());
+    }
+
+    #[test]
+    fn test_game_play_solver_strategy() {
+        let rules = Rules::official();
+        let mut game = Game::new(2, 0, rules.clone());
+        game.set_strategy(0, Box::new(SolverStrategy::new(rules).n_samples(2)));
+        game.set_strategy(1, Box::new(SaveWildsStrategy {}));
+        let result = game.play();
+        assert!(result.winner < 2);
+    }
+
+    #[test]
+    fn test_game_spin_roulette_penalty() {
+        let mut game = Game::with_seed(2, 0, Rules::no_mercy(), 42);
+        game.start();
+        let card = Card::new(Symbol::WildColorRoulette, Some(Color::Red));
+        game.dealer.discard(card);
+        game.pending_roulette = true;
+        game.step();
+        assert!(matches!(
+            game.events.last(),
+            Some(Event::PenaltyDrawn {
+                player: 1,
+                cause: PenaltyCause::Roulette,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_game_analyze() {
+        let rules = Rules::official();
+        let mut game = Game::with_seed(2, 0, rules.clone(), 42);
+        let mut solver = Solver::new(rules).max_depth(2);
+        assert_eq!(game.analyze(&mut solver), None);
+        game.step();
+        if let Some(values) = game.analyze(&mut solver) {
+            let values = values.into_iter().map(|(_, value)| value);
+            assert!(values.into_iter().all(|x| (0.0..=1.0).contains(&x)));
+        }
+    }
+
+    #[rstest]
+    #[case(Symbol::Draw2, Symbol::Draw2, Stacking::Off, false)]
+    #[case(Symbol::Draw2, Symbol::Draw2, Stacking::SameSymbol, true)]
+    #[case(Symbol::WildDraw4, Symbol::Draw2, Stacking::SameSymbol, false)]
+    #[case(Symbol::WildDraw4, Symbol::Draw2, Stacking::EqualOrHigher, true)]
+    #[case(Symbol::Draw2, Symbol::WildDraw4, Stacking::EqualOrHigher, false)]
+    #[case(Symbol::Skip, Symbol::Draw2, Stacking::EqualOrHigher, false)]
+    fn test_card_can_stack_onto(
+        #[case] symbol: Symbol,
+        #[case] top_symbol: Symbol,
+        #[case] stacking: Stacking,
+        #[case] expected: bool,
+    ) {
+        let cards = generate_cards(vec![
+            (symbol, Some(Color::Red)),
+            (top_symbol, Some(Color::Red)),
+        ]);
+        assert_eq!(cards[0].can_stack_onto(&cards[1], stacking), expected);
+    }
+
+    #[test]
+    fn test_player_cycle_eliminate() {
+        let mut players = PlayerCycle::new(4, 0);
+        let hands = vec![generate_cards(vec![(Symbol::Number(1), Some(Color::Red))]); 4];
+        players.take_hands(hands);
+        assert_eq!(players.next_index(), 0);
+        assert_eq!(players.next_index(), 1);
+
+        // eliminating the current player continues with the next player
+        assert_eq!(players.eliminate(1).len(), 1);
+        assert_eq!(players.next_index(), 2);
+        players.eliminate(3);
+        assert_eq!(players.n_active(), 2);
+        assert_eq!(players.next_index(), 0);
+        players.eliminate(2);
+
+        let result = players.last_player_result();
+        assert_eq!(result.winner, 0);
+        assert_eq!(result.placements, vec![0, 2, 3, 1]);
+    }
+
+    #[test]
+    fn test_game_eliminate_all_players_over_limit() {
+        let rules = Rules {
+            mercy_limit: Some(8),
+            ..Rules::no_mercy()
+        };
+        let mut game = Game::with_seed(3, 0, rules, 42);
+        for (index, n_cards) in [3, 1, 2].into_iter().enumerate() {
+            let cards = game.dealer.draw(n_cards);
+            game.players.players[index].take_cards(cards);
+        }
+        let result = game.eliminate_players().expect("game over");
+        assert_eq!(result.winner, 1);
+        assert_eq!(result.placements, vec![1, 2, 0]);
+    }
+
+    #[test]
+    fn test_player_cycle_hand_infos() {
+        let red = Card::new(Symbol::Number(5), Some(Color::Red));
+        let blue = Card::new(Symbol::Number(5), Some(Color::Blue));
+        let mut players = PlayerCycle::new(3, 0);
+        players.take_hands(vec![vec![red, blue], vec![blue], vec![blue]]);
+
+        // player 1 draws on red and keeps the drawn card
+        players.players[1].observe_draw(&red);
+        players.players[1].take_cards(vec![blue]);
+        let infos = players.hand_infos(0);
+        assert_eq!(infos[1].voids, vec![Color::Red]);
+        assert_eq!(infos[1].n_free, 1);
+
+        // after swapping hands, player 0 knows their old hand and the voids move along
+        players.swap_hands(0, 1);
+        let infos = players.hand_infos(0);
+        assert_eq!(infos[0].voids, vec![Color::Red]);
+        assert!(infos[1].voids.is_empty());
+        assert_eq!(infos[1].known_cards, vec![red, blue]);
+        assert!(players.hand_infos(2)[1].known_cards.is_empty());
+
+        // played cards are no longer known
+        players.forget_card(1, &red);
+        assert_eq!(players.hand_infos(0)[1].known_cards, vec![blue]);
+
+        // cards passed on in forward direction are known to the passing player
+        players.pass_hands();
+        let infos = players.hand_infos(2);
+        assert_eq!(infos[0].known_cards, vec![blue]);
+        assert!(infos[2].voids.is_empty());
+        assert_eq!(players.hand_infos(0)[1].voids, vec![Color::Red]);
+    }
+
+    #[test]
+    fn test_player_cycle_teams_result() {
+        let mut players = PlayerCycle::new(4, 0);
+        players.form_teams();
+        let hands = vec![
+            vec![],
+            generate_cards(vec![(Symbol::Number(5), Some(Color::Red))]),
+            generate_cards(vec![(Symbol::Skip, Some(Color::Red))]),
+            generate_cards(vec![(Symbol::Number(3), Some(Color::Red))]),
+        ];
+        for (player, hand) in players.players.iter_mut().zip(hands) {
+            player.hand = hand;
+        }
+        players.next();
+
+        // partners sit opposite each other and the partner's hand is not scored
+        assert_eq!(players.team_members(0), vec![0, 2]);
+        assert_eq!(players.team_members(3), vec![1, 3]);
+        let result = players.result();
+        assert_eq!(result.team, Some(0));
+        assert_eq!(result.points, 5 + 3);
+    }
+
+    #[test]
+    fn test_player_cycle_pass_to_partners() {
+        let mut players = PlayerCycle::new(4, 0);
+        players.form_teams();
+        let hands: Vec<Cards> = (0..4)
+            .map(|x| generate_cards(vec![(Symbol::Number(x), Some(Color::Red))]))
+            .collect();
+        players.take_hands(hands.clone());
+        players.pass_to_partners();
+        assert_eq!(players.players[0].hand, hands[2]);
+        assert_eq!(players.players[1].hand, hands[3]);
+        assert_eq!(players.players[2].hand, hands[0]);
+        assert_eq!(players.players[3].hand, hands[1]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_player_cycle_teams_odd_players() {
+        PlayerCycle::new(5, 0).form_teams();
+    }
+
+    #[test]
+    fn test_player_view_is_partner() {
+        let view = PlayerView {
+            index: 1,
+            hand: vec![],
+            next: 2,
+            hand_sizes: vec![7; 6],
+            teams: Some(vec![0, 1, 2, 0, 1, 2]),
+            top_card: Card::new(Symbol::Number(1), Some(Color::Red)),
+            pile: vec![],
+            unknown_cards: vec![],
+            direction: Direction::Forward,
+            pending_draw: 0,
+            hand_infos: vec![HandInfo::default(); 6],
+        };
+        assert!(view.is_partner(4));
+        assert!(!view.is_partner(1));
+        assert!(view.is_opponent(2));
+        assert!(!view.is_opponent(4));
+    }
+
+    #[test]
+    fn test_player_cycle_swap_hands() {
+        let mut players = PlayerCycle::new(3, 0);
+        let hands = vec![
+            generate_cards(vec![(Symbol::Number(1), Some(Color::Red))]),
+            generate_cards(vec![(Symbol::Number(2), Some(Color::Red))]),
+            generate_cards(vec![(Symbol::Number(3), Some(Color::Red))]),
+        ];
+        players.take_hands(hands.clone());
+        players.swap_hands(0, 2);
+        assert_eq!(players.players[0].hand, hands[2]);
+        assert_eq!(players.players[1].hand, hands[1]);
+        assert_eq!(players.players[2].hand, hands[0]);
+    }
+
+    #[rstest]
+    #[case(false, [2, 0, 1])]
+    #[case(true, [1, 2, 0])]
+    fn test_player_cycle_pass_hands(#[case] reversed: bool, #[case] expected: [usize; 3]) {
+        let mut players = PlayerCycle::new(3, 0);
+        let hands = vec![
+            generate_cards(vec![(Symbol::Number(1), Some(Color::Red))]),
+            generate_cards(vec![(Symbol::Number(2), Some(Color::Red))]),
+            generate_cards(vec![(Symbol::Number(3), Some(Color::Red))]),
+        ];
+        players.take_hands(hands.clone());
+        players.next();
+        if reversed {
+            players.reverse();
+        }
+        players.pass_hands();
+        for (player, index) in players.players.iter().zip(expected) {
+            assert_eq!(player.hand, hands[index]);
+        }
+    }
+
+    #[test]
+    fn test_game_find_jump_in() {
+        let rules = Rules {
+            jump_in: true,
+            ..Rules::official()
         };
+        let mut game = Game::new(N_PLAYERS, 0, rules);
+        let card = generate_cards(vec![(Symbol::Number(5), Some(Color::Blue))])[0];
+        for player in game.players.players.iter_mut() {
+            player.strategy = Arc::new(AttentiveStrategy {});
+            player.hand.retain(|x| *x != card);
+        }
+        assert_eq!(game.find_jump_in(0, card), None);
+
+        game.players.players[2].take_cards(vec![card]);
+        assert_eq!(game.find_jump_in(0, card), Some((2, card)));
+    }
+
+    #[test]
+    fn test_filter_legal_cards_wild_draw_4_unrestricted() {
+        let top_card = Card::new(Symbol::Number(1), Some(Color::Red));
+        let cards = generate_cards(vec![
+            (Symbol::Number(2), Some(Color::Red)),
+            (Symbol::WildDraw4, None),
+        ]);
+        for rule in [WildDraw4Rule::Challenge, WildDraw4Rule::Unrestricted] {
+            let legal_cards = filter_legal_cards(cards.clone(), top_card, rule);
+            assert_eq!(legal_cards, cards);
+        }
+    }
+
+    #[test]
+    fn test_filter_legal_cards_top_card_red_1() {
+        let top_card = Card::new(Symbol::Number(1), Some(Color::Red));
 
         let cards = generate_cards(vec![
             // legal, same symbol
@@ -674,16 +2877,13 @@ mod tests {
             (Symbol::Number(3), Some(Color::Green)),
             (Symbol::WildDraw4, None),
         ]);
-        let legal_cards = filter_legal_cards(cards.clone(), top_card);
+        let legal_cards = filter_legal_cards(cards.clone(), top_card, WildDraw4Rule::Restricted);
         assert_eq!(legal_cards, cards[..=6]);
     }
 
     #[test]
     fn test_filter_legal_cards_top_card_blue_3() {
-        let top_card = Card {
-            symbol: Symbol::Number(3),
-            color: Some(Color::Blue),
-        };
+        let top_card = Card::new(Symbol::Number(3), Some(Color::Blue));
         let cards = generate_cards(vec![
             // legal, same symbol
             (Symbol::Number(3), Some(Color::Red)),
@@ -700,16 +2900,13 @@ mod tests {
             (Symbol::Number(9), Some(Color::Yellow)),
             (Symbol::WildDraw4, None),
         ]);
-        let legal_cards = filter_legal_cards(cards.clone(), top_card);
+        let legal_cards = filter_legal_cards(cards.clone(), top_card, WildDraw4Rule::Restricted);
         assert_eq!(legal_cards, cards[..=6]);
     }
 
     #[test]
     fn test_filter_legal_cards_top_card_yellow_skip() {
-        let top_card = Card {
-            symbol: Symbol::Skip,
-            color: Some(Color::Yellow),
-        };
+        let top_card = Card::new(Symbol::Skip, Some(Color::Yellow));
         let cards = generate_cards(vec![
             // legal, same symbol
             (Symbol::Skip, Some(Color::Red)),
@@ -726,16 +2923,13 @@ mod tests {
             (Symbol::Number(2), Some(Color::Red)),
             (Symbol::WildDraw4, None),
         ]);
-        let legal_cards = filter_legal_cards(cards.clone(), top_card);
+        let legal_cards = filter_legal_cards(cards.clone(), top_card, WildDraw4Rule::Restricted);
         assert_eq!(legal_cards, cards[..=6]);
     }
 
     #[test]
     fn test_filter_legal_cards_no_color_matches_wild_draw_4() {
-        let top_card = Card {
-            symbol: Symbol::Number(0),
-            color: Some(Color::Green),
-        };
+        let top_card = Card::new(Symbol::Number(0), Some(Color::Green));
         let cards = generate_cards(vec![
             // legal, same symbol
             (Symbol::Number(0), Some(Color::Red)),
@@ -747,7 +2941,21 @@ mod tests {
             (Symbol::Number(6), Some(Color::Blue)),
             (Symbol::Number(2), Some(Color::Red)),
         ]);
-        let legal_cards = filter_legal_cards(cards.clone(), top_card);
+        let legal_cards = filter_legal_cards(cards.clone(), top_card, WildDraw4Rule::Restricted);
         assert_eq!(legal_cards, cards[..=3]);
     }
+
+    #[test]
+    fn test_filter_legal_cards_color_match_color_roulette() {
+        let top_card = Card::new(Symbol::Number(0), Some(Color::Green));
+        let cards = generate_cards(vec![
+            // legal, color roulette draws no fixed penalty and is not restricted
+            (Symbol::Number(6), Some(Color::Green)),
+            (Symbol::WildColorRoulette, None),
+            // illegal, color match
+            (Symbol::WildDraw4, None),
+        ]);
+        let legal_cards = filter_legal_cards(cards.clone(), top_card, WildDraw4Rule::Restricted);
+        assert_eq!(legal_cards, cards[..=1]);
+    }
 }
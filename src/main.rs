@@ -1,5 +1,201 @@
-use uno::run;
+use std::env;
+use std::net::TcpListener;
+use std::process;
+use std::str::FromStr;
+use std::time::Duration;
+use uno::{
+    analyze, fmt_ratings, fmt_standings, run, Pairing, Ratings, Rules, Server, Tournament,
+    MAX_PLAYERS,
+};
+
+const USAGE: &str = "Usage:
+  uno [--rules <file.toml|preset>] [--players <n>]
+  uno analyze [--rules <file.toml|preset>] [--seed <n>] [--players <n>] [--depth <n>]
+  uno serve [--rules <file.toml|preset>] [--seed <n>] [--port <n>] [--players <n>]
+            [--web-port <n>] [--timeout <seconds>] [--expiry <seconds>] [--bot <strategy>]
+            [--log-dir <dir>] [--ratings <file.json>]
+  uno tournament <strategy>... [--rules <file.toml|preset>] [--seed <n>] [--players <n>]
+            [--games <n>] [--pairing <round-robin|swiss>] [--rounds <n>]
+            [--ratings <file.json>]
+  uno ratings [--ratings <file.json>]";
+const PORT: u16 = 7777;
+const N_PLAYERS: usize = 4;
+const RATINGS: &str = "ratings.json"; // default ratings file
+
+fn exit_with_usage() -> ! {
+    eprintln!("{}", USAGE);
+    process::exit(1);
+}
+
+fn exit_with_error(error: impl std::fmt::Display) -> ! {
+    eprintln!("{}", error);
+    process::exit(1);
+}
+
+// parse flag value or exit with usage
+fn parse<T: FromStr>(value: &str) -> T {
+    value.parse().unwrap_or_else(|_| exit_with_usage())
+}
 
 fn main() {
-    run()
+    // parse optional command followed by strategy specs for tournaments and flags with values
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    let command = match args.first().map(String::as_str) {
+        Some(command @ ("analyze" | "serve" | "tournament" | "ratings")) => command.to_string(),
+        _ => String::new(),
+    };
+    if !command.is_empty() {
+        args.remove(0);
+    }
+    let is_analyze = command == "analyze";
+    let is_serve = command == "serve";
+    let is_tournament = command == "tournament";
+    let is_ratings = command == "ratings";
+    let specs: Vec<String> = match is_tournament {
+        true => args
+            .iter()
+            .take_while(|x| !x.starts_with("--"))
+            .cloned()
+            .collect(),
+        false => vec![],
+    };
+    args.drain(..specs.len());
+
+    let mut rules = Rules::default();
+    let mut seed = None;
+    let mut port = PORT;
+    let mut web_port = None;
+    let mut n_players = None;
+    let mut timeout = None;
+    let mut expiry = None;
+    let mut bot = None;
+    let mut log_dir = None;
+    let mut n_games = None;
+    let mut pairing = None;
+    let mut rounds = None;
+    let mut ratings = None;
+    let mut max_depth = None;
+    for pair in args.chunks(2) {
+        match pair {
+            [flag, value] if flag == "--rules" => {
+                rules = Rules::load(value).unwrap_or_else(|error| exit_with_error(error))
+            }
+            [flag, value] if flag == "--seed" && !command.is_empty() => seed = Some(parse(value)),
+            [flag, value] if flag == "--depth" && is_analyze => match parse(value) {
+                0 => exit_with_usage(),
+                depth => max_depth = Some(depth),
+            },
+            [flag, value] if flag == "--port" && is_serve => port = parse(value),
+            [flag, value] if flag == "--web-port" && is_serve => web_port = Some(parse(value)),
+            [flag, value] if flag == "--players" && !is_ratings => n_players = Some(parse(value)),
+            [flag, value] if flag == "--timeout" && is_serve => timeout = Some(parse(value)),
+            [flag, value] if flag == "--expiry" && is_serve => expiry = Some(parse(value)),
+            [flag, value] if flag == "--bot" && is_serve => bot = Some(value.clone()),
+            [flag, value] if flag == "--log-dir" && is_serve => log_dir = Some(value.clone()),
+            [flag, value] if flag == "--games" && is_tournament => n_games = Some(parse(value)),
+            [flag, value] if flag == "--pairing" && is_tournament => pairing = Some(value.clone()),
+            [flag, value] if flag == "--rounds" && is_tournament => rounds = Some(parse(value)),
+            [flag, value] if flag == "--ratings" && (is_serve || is_tournament || is_ratings) => {
+                ratings = Some(value.clone())
+            }
+            _ => exit_with_usage(),
+        }
+    }
+
+    if let Some(n_players) = n_players.filter(|x| !(2..=MAX_PLAYERS).contains(x)) {
+        exit_with_error(format!(
+            "number of players must be between 2 and {}, got {}",
+            MAX_PLAYERS, n_players
+        ));
+    }
+
+    // check that the deck holds the hands of a local game and the teams can be formed
+    if matches!(command.as_str(), "" | "analyze") {
+        rules
+            .validate_players(n_players.unwrap_or(N_PLAYERS))
+            .unwrap_or_else(|error| exit_with_error(error));
+    }
+
+    match command.as_str() {
+        "analyze" => analyze(
+            rules,
+            n_players.unwrap_or(N_PLAYERS),
+            seed.unwrap_or_else(rand::random),
+            max_depth,
+        ),
+        "serve" => {
+            let mut server = Server::new(n_players.unwrap_or(N_PLAYERS), rules);
+            if let Some(seconds) = timeout {
+                server = server.timeout(Duration::from_secs_f64(seconds));
+            }
+            if let Some(seconds) = expiry {
+                server = server.expiry(Duration::from_secs_f64(seconds));
+            }
+            if let Some(bot) = bot {
+                server = server.bot(&bot);
+            }
+            if let Some(seed) = seed {
+                server = server.seed(seed);
+            }
+            if let Some(log_dir) = log_dir {
+                server = server.log_dir(log_dir);
+            }
+            if let Some(ratings) = ratings {
+                server = server.ratings(ratings);
+            }
+            if let Some(web_port) = web_port {
+                let web = TcpListener::bind(("0.0.0.0", web_port))
+                    .unwrap_or_else(|error| exit_with_error(error));
+                server = server.web(web);
+            }
+            let listener =
+                TcpListener::bind(("0.0.0.0", port)).unwrap_or_else(|error| exit_with_error(error));
+            server
+                .serve(listener)
+                .unwrap_or_else(|error| exit_with_error(error));
+        }
+        "tournament" => {
+            // play as many swiss rounds as needed to find a single winner by default
+            let n_rounds = (specs.len().max(2) as f64).log2().ceil() as usize;
+            let pairing = match pairing.as_deref() {
+                None | Some("round-robin") => Pairing::RoundRobin,
+                Some("swiss") => Pairing::Swiss {
+                    rounds: rounds.unwrap_or(n_rounds),
+                },
+                Some(_) => exit_with_usage(),
+            };
+            let seed = seed.unwrap_or_else(rand::random);
+            let mut tournament = Tournament::new(specs, rules).seed(seed).pairing(pairing);
+            if let Some(n_players) = n_players {
+                tournament = tournament.n_players(n_players);
+            }
+            if let Some(n_games) = n_games {
+                tournament = tournament.n_games(n_games);
+            }
+            let mut rated = ratings.map(|path| {
+                let ratings = Ratings::load(&path).unwrap_or_else(|error| exit_with_error(error));
+                (path, ratings)
+            });
+            let standings = match &mut rated {
+                Some((_, ratings)) => tournament.run_rated(ratings),
+                None => tournament.run(),
+            }
+            .unwrap_or_else(|error| exit_with_error(error));
+            println!("Seed: {}", seed);
+            println!("{}", fmt_standings(&standings));
+            if let Some((path, ratings)) = rated {
+                ratings
+                    .save(&path)
+                    .unwrap_or_else(|error| exit_with_error(error));
+                println!();
+                println!("{}", fmt_ratings(&ratings));
+            }
+        }
+        "ratings" => {
+            let path = ratings.unwrap_or_else(|| RATINGS.to_string());
+            let ratings = Ratings::load(path).unwrap_or_else(|error| exit_with_error(error));
+            println!("{}", fmt_ratings(&ratings));
+        }
+        _ => run(rules, n_players.unwrap_or(N_PLAYERS)),
+    }
 }
@@ -0,0 +1,446 @@
+use crate::greedy::most_held_color;
+use crate::{
+    create_strategy, is_verbose, set_verbose, Card, Cards, Color, Game, GameResult, Play, Rules,
+    SaveWildsStrategy, Side, Strategy, Symbol, PRESETS, STRATEGIES,
+};
+use pyo3::exceptions::{PyIndexError, PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyString;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Error raised by a Python strategy during a step, shared between the strategies and the
+/// game.
+type PyErrorSlot = Rc<RefCell<Option<PyErr>>>;
+
+/// Get name of `color`.
+fn color_name(color: Color) -> String {
+    format!("{:?}", color)
+}
+
+/// Get color with `name`, as given by [`color_name`].
+fn parse_color(name: &str) -> PyResult<Color> {
+    let mut colors = Side::Light.colors().into_iter().chain(Side::Dark.colors());
+    colors
+        .find(|&color| color_name(color) == name)
+        .ok_or_else(|| PyValueError::new_err(format!("unknown color: {}", name)))
+}
+
+/// Get rules of Python `rules`, defaulting to the official rules.
+fn unwrap_rules(rules: Option<PyRef<PyRules>>) -> Rules {
+    rules.map(|x| x.rules.clone()).unwrap_or_default()
+}
+
+/// Card, where wild cards have no color until played.
+#[pyclass(name = "Card", frozen, eq, hash)]
+#[derive(Clone, PartialEq, Hash)]
+struct PyCard {
+    card: Card,
+}
+
+#[pymethods]
+impl PyCard {
+    /// Symbol of the face up, e.g. "7", "Skip" or "WildDraw4".
+    #[getter]
+    fn symbol(&self) -> String {
+        match self.card.symbol {
+            Symbol::Number(number) => number.to_string(),
+            symbol => symbol.to_string(),
+        }
+    }
+
+    /// Color of the face up, e.g. "Red", or `None` for wild cards without color.
+    #[getter]
+    fn color(&self) -> Option<String> {
+        self.card.color.map(color_name)
+    }
+
+    #[getter]
+    fn is_wild(&self) -> bool {
+        self.card.is_wild()
+    }
+
+    /// Points scored for the card left in hand at the end of a game.
+    #[getter]
+    fn points(&self) -> u32 {
+        self.card.points()
+    }
+
+    /// Get copy of wild card with the chosen `color`.
+    fn with_color(&self, color: &str) -> PyResult<Self> {
+        if !self.card.is_wild() {
+            return Err(PyValueError::new_err("only wild cards can be colored"));
+        }
+        let mut card = self.card;
+        card.color = Some(parse_color(color)?);
+        Ok(Self { card })
+    }
+
+    fn __repr__(&self) -> String {
+        match self.color() {
+            Some(color) => format!("Card({}, {})", self.symbol(), color),
+            None => format!("Card({})", self.symbol()),
+        }
+    }
+}
+
+/// Game rules, created from a preset or loaded from a TOML file.
+#[pyclass(name = "Rules")]
+#[derive(Clone)]
+struct PyRules {
+    rules: Rules,
+}
+
+#[pymethods]
+impl PyRules {
+    /// Create rules from `preset`.
+    #[new]
+    #[pyo3(signature = (preset = "official"))]
+    fn new(preset: &str) -> PyResult<Self> {
+        let rules = Rules::preset(preset).ok_or_else(|| {
+            let message = format!("unknown preset: {}, expected one of {:?}", preset, PRESETS);
+            PyValueError::new_err(message)
+        })?;
+        Ok(Self { rules })
+    }
+
+    /// Load rules from preset or TOML file.
+    #[staticmethod]
+    fn load(name_or_path: &str) -> PyResult<Self> {
+        let rules = Rules::load(name_or_path).map_err(|x| PyValueError::new_err(x.to_string()))?;
+        Ok(Self { rules })
+    }
+
+    /// Parse rules from TOML string.
+    #[staticmethod]
+    fn from_toml(s: &str) -> PyResult<Self> {
+        let rules = Rules::from_toml(s).map_err(|x| PyValueError::new_err(x.to_string()))?;
+        Ok(Self { rules })
+    }
+
+    fn to_toml(&self) -> String {
+        self.rules.to_toml()
+    }
+
+    #[getter]
+    fn n_initial_cards(&self) -> usize {
+        self.rules.n_initial_cards
+    }
+
+    #[getter]
+    fn teams(&self) -> bool {
+        self.rules.teams
+    }
+
+    #[getter]
+    fn target_score(&self) -> u32 {
+        self.rules.target_score
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Rules({:?})", self.rules.to_toml())
+    }
+}
+
+/// Result of a single game.
+#[pyclass(name = "GameResult", frozen, get_all)]
+struct PyGameResult {
+    winner: usize,
+    team: Option<usize>,
+    points: u32,
+    placements: Vec<usize>,
+}
+
+impl From<GameResult> for PyGameResult {
+    fn from(result: GameResult) -> Self {
+        Self {
+            winner: result.winner,
+            team: result.team,
+            points: result.points,
+            placements: result.placements,
+        }
+    }
+}
+
+#[pymethods]
+impl PyGameResult {
+    fn __repr__(&self) -> String {
+        format!(
+            "GameResult(winner={}, points={}, placements={:?})",
+            self.winner, self.points, self.placements
+        )
+    }
+}
+
+/// Strategy delegating to a Python object, which implements `select_card(legal_cards)`
+/// returning one of the legal cards, with wild cards colored, or `None` to draw, and
+/// optionally `select_color(hand)` returning a color name. Errors raised by the Python
+/// object and illegal plays are stored in `error` and raised once the step is over, while
+/// this and every later decision of the step fall back to saving wild cards.
+struct PyStrategy {
+    object: Py<PyAny>,
+    error: PyErrorSlot,
+}
+
+impl PyStrategy {
+    fn to_py_cards(cards: &[Card]) -> Vec<PyCard> {
+        cards.iter().map(|&card| PyCard { card }).collect()
+    }
+
+    /// Run `call` on the Python object, unless an error is pending, and store its error.
+    fn call<T>(&self, call: impl FnOnce(Python) -> PyResult<T>) -> Option<T> {
+        if self.error.borrow().is_some() {
+            return None;
+        }
+        match Python::with_gil(call) {
+            Ok(value) => Some(value),
+            Err(error) => {
+                *self.error.borrow_mut() = Some(error);
+                None
+            }
+        }
+    }
+}
+
+impl Strategy for PyStrategy {
+    fn select_card(&self, legal_cards: Cards) -> Play {
+        let play = self.call(|py| {
+            let cards = Self::to_py_cards(&legal_cards);
+            let play = self.object.call_method1(py, "select_card", (cards,))?;
+            let Some(card) = play.extract::<Option<PyCard>>(py)?.map(|x| x.card) else {
+                return Ok(None);
+            };
+            if !legal_cards
+                .iter()
+                .any(|x| x.is_equal_ignore_wild_color(&card))
+            {
+                let message = format!("strategy selected illegal card: {:?}", card);
+                return Err(PyValueError::new_err(message));
+            }
+            if card.color.is_none() {
+                let message = "strategy selected wild card without color";
+                return Err(PyValueError::new_err(message));
+            }
+            Ok(Some(card))
+        });
+        play.unwrap_or_else(|| SaveWildsStrategy {}.select_card(legal_cards))
+    }
+
+    fn select_color(&self, hand: &Cards, side: Side) -> Color {
+        let color = self.call(|py| {
+            let object = self.object.bind(py);
+            if !object.hasattr("select_color")? {
+                return Ok(None);
+            }
+            let color = object.call_method1("select_color", (Self::to_py_cards(hand),))?;
+            parse_color(&color.extract::<String>()?).map(Some)
+        });
+        color
+            .flatten()
+            .unwrap_or_else(|| most_held_color(hand, side))
+    }
+}
+
+/// Game between players following built-in or Python strategies, played step by step.
+#[pyclass(name = "Game", unsendable)]
+struct PyGame {
+    game: Game,
+    result: Option<GameResult>,
+    error: PyErrorSlot,
+}
+
+#[pymethods]
+impl PyGame {
+    /// Create game for `n_players` with `rules`, where the player after the `dealer`
+    /// starts, shuffling cards with a random number generator seeded with `seed`.
+    #[new]
+    #[pyo3(signature = (n_players, rules = None, seed = None, dealer = 0))]
+    fn new(
+        n_players: usize,
+        rules: Option<PyRef<PyRules>>,
+        seed: Option<u64>,
+        dealer: usize,
+    ) -> PyResult<Self> {
+        if !(2..=crate::PLAYER_NAMES.len()).contains(&n_players) || dealer >= n_players {
+            return Err(PyValueError::new_err("invalid number of players or dealer"));
+        }
+        let rules = unwrap_rules(rules);
+        validate_players(&rules, n_players)?;
+        let seed = seed.unwrap_or_else(rand::random);
+        let game = Game::with_seed(n_players, dealer, rules, seed);
+        Ok(Self {
+            game,
+            result: None,
+            error: PyErrorSlot::default(),
+        })
+    }
+
+    #[getter]
+    fn n_players(&self) -> usize {
+        self.game.players.players.len()
+    }
+
+    /// Top card of the pile, or `None` before the game has started.
+    #[getter]
+    fn top_card(&self) -> Option<PyCard> {
+        let card = self.game.dealer.pile.last()?;
+        Some(PyCard { card: *card })
+    }
+
+    /// Result of the game, or `None` while the game is not over.
+    #[getter]
+    fn result(&self) -> Option<PyGameResult> {
+        self.result.clone().map(PyGameResult::from)
+    }
+
+    /// Get hand of player at `index`.
+    fn hand(&self, index: usize) -> PyResult<Vec<PyCard>> {
+        self.check_index(index)?;
+        Ok(PyStrategy::to_py_cards(self.game.hand(index)))
+    }
+
+    /// Get number of cards in the hand of each player.
+    fn hand_sizes(&self) -> Vec<usize> {
+        self.game.players.hand_sizes()
+    }
+
+    /// Set `strategy` of player at `index`, given by the name of a built-in strategy or
+    /// as Python object implementing `select_card`.
+    fn set_strategy(&mut self, index: usize, strategy: Bound<PyAny>) -> PyResult<()> {
+        self.check_index(index)?;
+        let strategy: Box<dyn Strategy> = match strategy.downcast::<PyString>() {
+            Ok(name) => create_named_strategy(name.to_str()?, &self.game.rules)?,
+            Err(_) if strategy.hasattr("select_card")? => Box::new(PyStrategy {
+                object: strategy.unbind(),
+                error: self.error.clone(),
+            }),
+            Err(_) => {
+                let message = "strategy must be a name or implement select_card";
+                return Err(PyValueError::new_err(message));
+            }
+        };
+        self.game.set_strategy(index, strategy);
+        Ok(())
+    }
+
+    /// Play turn of next player and return the result if the game is over, or raise the
+    /// first error of a Python strategy during the turn, which is completed by saving wild
+    /// cards instead.
+    fn step(&mut self) -> PyResult<Option<PyGameResult>> {
+        if self.result.is_some() {
+            return Err(PyRuntimeError::new_err("game over"));
+        }
+        self.result = self.game.step();
+        if let Some(error) = self.error.borrow_mut().take() {
+            return Err(error);
+        }
+        Ok(self.result())
+    }
+
+    /// Play game until it is over and return the result.
+    fn play(&mut self) -> PyResult<PyGameResult> {
+        loop {
+            if let Some(result) = self.step()? {
+                break Ok(result);
+            }
+        }
+    }
+}
+
+impl PyGame {
+    fn check_index(&self, index: usize) -> PyResult<()> {
+        match index < self.n_players() {
+            true => Ok(()),
+            false => Err(PyIndexError::new_err("player index out of range")),
+        }
+    }
+}
+
+/// Check that `rules` can be played by `n_players`, or raise error, see
+/// [`Rules::validate_players`].
+fn validate_players(rules: &Rules, n_players: usize) -> PyResult<()> {
+    rules
+        .validate_players(n_players)
+        .map_err(|error| PyValueError::new_err(error.to_string()))
+}
+
+/// Create built-in strategy by `name`, or raise error for unknown names.
+fn create_named_strategy(name: &str, rules: &Rules) -> PyResult<Box<dyn Strategy>> {
+    create_strategy(name, rules).ok_or_else(|| {
+        let message = format!(
+            "unknown strategy: {}, expected one of {:?}",
+            name, STRATEGIES
+        );
+        PyValueError::new_err(message)
+    })
+}
+
+/// Play `n_games` between built-in `strategies` other than bots, one per player, with
+/// `rules`, rotating the dealer and seeding game `i` with `seed + i`, and return the
+/// winner of each game. Games are played without holding the GIL, so that other Python
+/// threads can run.
+#[pyfunction]
+#[pyo3(signature = (strategies, rules = None, n_games = 1, seed = None))]
+fn simulate(
+    py: Python,
+    strategies: Vec<String>,
+    rules: Option<PyRef<PyRules>>,
+    n_games: usize,
+    seed: Option<u64>,
+) -> PyResult<Vec<usize>> {
+    let rules = unwrap_rules(rules);
+    let n_players = strategies.len();
+    if !(2..=crate::PLAYER_NAMES.len()).contains(&n_players) {
+        return Err(PyValueError::new_err("invalid number of players"));
+    }
+    validate_players(&rules, n_players)?;
+    for name in &strategies {
+        // bots would be spawned for every game, without a way to report their errors
+        if name.starts_with("bot:") {
+            let message = format!("bots cannot be simulated: {}", name);
+            return Err(PyValueError::new_err(message));
+        }
+        create_named_strategy(name, &rules)?;
+    }
+    let seed = seed.unwrap_or_else(rand::random);
+
+    let winners = py.allow_threads(|| {
+        let verbose = is_verbose();
+        set_verbose(false);
+        let winners = (0..n_games)
+            .map(|i| {
+                let seed = seed.wrapping_add(i as u64);
+                let mut game = Game::with_seed(n_players, i % n_players, rules.clone(), seed);
+                for (index, name) in strategies.iter().enumerate() {
+                    let strategy = create_strategy(name, &rules).expect("known strategy");
+                    game.set_strategy(index, strategy);
+                }
+                game.play().winner
+            })
+            .collect();
+        set_verbose(verbose);
+        winners
+    });
+    Ok(winners)
+}
+
+/// Enable or disable printing game progress on the current thread.
+#[pyfunction(name = "set_verbose")]
+fn py_set_verbose(verbose: bool) {
+    set_verbose(verbose);
+}
+
+/// Python extension module, with game progress not printed by default.
+#[pymodule]
+fn uno(m: &Bound<PyModule>) -> PyResult<()> {
+    set_verbose(false);
+    m.add_class::<PyCard>()?;
+    m.add_class::<PyRules>()?;
+    m.add_class::<PyGameResult>()?;
+    m.add_class::<PyGame>()?;
+    m.add_function(wrap_pyfunction!(simulate, m)?)?;
+    m.add_function(wrap_pyfunction!(py_set_verbose, m)?)?;
+    m.add("PRESETS", PRESETS.to_vec())?;
+    m.add("STRATEGIES", STRATEGIES.to_vec())?;
+    Ok(())
+}
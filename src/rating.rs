@@ -0,0 +1,173 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::Path;
+
+const INITIAL_RATING: f64 = 1500.0;
+const K: f64 = 32.0; // maximum rating change per game
+
+/// Rating of a player or strategy.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Rating {
+    /// Elo rating, starting at 1500.
+    pub rating: f64,
+    /// Number of rated games played.
+    pub n_games: usize,
+}
+
+impl Default for Rating {
+    fn default() -> Self {
+        Self {
+            rating: INITIAL_RATING,
+            n_games: 0,
+        }
+    }
+}
+
+/// Multiplayer Elo ratings by name, updated from game placements by treating each pair
+/// of players as a single two-player game, with the rating change scaled down by the
+/// number of opponents.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Ratings {
+    players: BTreeMap<String, Rating>,
+}
+
+impl Ratings {
+    /// Load ratings from JSON file at `path`, starting without ratings if it does not
+    /// exist.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(s) => serde_json::from_str(&s).map_err(io::Error::other),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Save ratings as JSON file at `path`.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let s = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        fs::write(path, s + "\n")
+    }
+
+    /// Get rating of player with `name`.
+    pub fn get(&self, name: &str) -> Rating {
+        self.players.get(name).copied().unwrap_or_default()
+    }
+
+    /// Update ratings of players with `names` from `placements` of their indexes from
+    /// first to last place, see [`GameResult`](crate::GameResult).
+    pub fn update(&mut self, names: &[String], placements: &[usize]) {
+        let n_opponents = placements.len().saturating_sub(1).max(1) as f64;
+        let ratings: Vec<f64> = names.iter().map(|x| self.get(x).rating).collect();
+
+        // compute changes from the ratings before the game
+        let mut changes = vec![0.0; names.len()];
+        for (rank, &winner) in placements.iter().enumerate() {
+            for &loser in &placements[rank + 1..] {
+                let expected = expected_score(ratings[winner], ratings[loser]);
+                changes[winner] += K * (1.0 - expected) / n_opponents;
+                changes[loser] -= K * (1.0 - expected) / n_opponents;
+            }
+        }
+        for (name, change) in names.iter().zip(changes) {
+            let rating = self.players.entry(name.clone()).or_default();
+            rating.rating += change;
+            rating.n_games += 1;
+        }
+    }
+
+    /// Get names and ratings from highest to lowest rating.
+    pub fn ranking(&self) -> Vec<(&str, Rating)> {
+        let mut ranking: Vec<_> = self.players.iter().map(|(x, y)| (x.as_str(), *y)).collect();
+        ranking.sort_by(|a, b| b.1.rating.total_cmp(&a.1.rating));
+        ranking
+    }
+}
+
+/// Get expected score of player with `rating` against player with `other` rating.
+fn expected_score(rating: f64, other: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf((other - rating) / 400.0))
+}
+
+/// Format `ratings` as table.
+pub fn fmt_ratings(ratings: &Ratings) -> String {
+    let ranking = ratings.ranking();
+    let width = ranking.iter().map(|x| x.0.len()).max().unwrap_or(0).max(4);
+    let mut lines = vec![format!(
+        "{:<4} {:<width$} {:>7} {:>6}",
+        "Rank", "Name", "Rating", "Games"
+    )];
+    for (rank, (name, rating)) in ranking.iter().enumerate() {
+        lines.push(format!(
+            "{:<4} {:<width$} {:>7.0} {:>6}",
+            rank + 1,
+            name,
+            rating.rating,
+            rating.n_games
+        ));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*; // bring private functions into scope
+    use rstest::rstest;
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|x| x.to_string()).collect()
+    }
+
+    #[rstest]
+    #[case(1500.0, 1500.0, 0.5)]
+    #[case(1900.0, 1500.0, 10.0 / 11.0)]
+    #[case(1500.0, 1900.0, 1.0 / 11.0)]
+    fn test_expected_score(#[case] rating: f64, #[case] other: f64, #[case] expected: f64) {
+        assert!((expected_score(rating, other) - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_update_two_players() {
+        let mut ratings = Ratings::default();
+        ratings.update(&names(&["A", "B"]), &[1, 0]);
+        assert_eq!(ratings.get("A").rating, 1484.0);
+        assert_eq!(ratings.get("B").rating, 1516.0);
+        assert_eq!(ratings.get("B").n_games, 1);
+        assert_eq!(ratings.get("C"), Rating::default());
+    }
+
+    #[test]
+    fn test_update_multiplayer() {
+        let mut ratings = Ratings::default();
+        ratings.update(&names(&["A", "B", "C", "D"]), &[2, 0, 3, 1]);
+        let ranking: Vec<_> = ratings.ranking().iter().map(|x| x.0).collect();
+        assert_eq!(ranking, ["C", "A", "D", "B"]);
+        assert_eq!(ratings.get("C").rating, 1516.0);
+        let total: f64 = ratings.ranking().iter().map(|x| x.1.rating).sum();
+        assert!((total - 4.0 * INITIAL_RATING).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_ratings_save_and_load() {
+        let path = std::env::temp_dir().join(format!("uno-ratings-{}.json", rand::random::<u64>()));
+        assert_eq!(Ratings::load(&path).unwrap(), Ratings::default());
+
+        let mut ratings = Ratings::default();
+        ratings.update(&names(&["Ann", "bot:./my_bot"]), &[0, 1]);
+        ratings.save(&path).unwrap();
+        assert_eq!(Ratings::load(&path).unwrap(), ratings);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_fmt_ratings() {
+        let mut ratings = Ratings::default();
+        ratings.update(&names(&["Ann", "save-wilds"]), &[0, 1]);
+        let table = fmt_ratings(&ratings);
+        let lines: Vec<_> = table.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[1].starts_with("1    Ann"));
+        assert!(lines[1].ends_with("1516      1"));
+    }
+}
@@ -0,0 +1,412 @@
+use crate::deck::DeckSpec;
+use crate::draw::{DeckDraw, DrawSource, Launcher};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+const N_INITIAL_CARDS: usize = 7; // number of cards in initial player hands
+const UNO_PENALTY: usize = 2; // number of cards drawn when caught not calling "Uno"
+const TARGET_SCORE: u32 = 500; // number of points needed to win a match
+const MERCY_LIMIT: usize = 25; // number of cards at which a player is eliminated in No Mercy
+const MIN_PLAYERS: usize = 2;
+const MIN_TEAM_PLAYERS: usize = 4; // number of players needed in partnership mode
+
+/// Names of available rule presets.
+pub const PRESETS: [&str; 6] = [
+    "official",
+    "house-classic",
+    "no-mercy",
+    "flip",
+    "attack",
+    "partners",
+];
+
+/// How to handle the first card flipped onto the pile at the start of a game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FirstCardRule {
+    /// Official rules: a wild card stays on the pile and the first player chooses its
+    /// color, only a wild draw 4 is reshuffled into the deck.
+    Official,
+    /// Simplified rules: every wild card is returned to the deck.
+    Simplified,
+}
+
+/// How many cards a player draws when they cannot or do not want to play.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DrawMode {
+    /// Draw a single card, which may be played right away.
+    DrawOne,
+    /// Keep drawing until a playable card is drawn, which may be played right away.
+    UntilPlayable,
+}
+
+/// Whether a player hit by a draw card can pass the penalty on by playing another one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Stacking {
+    /// Draw penalties cannot be stacked.
+    Off,
+    /// Draw cards can be stacked onto draw cards with the same symbol.
+    SameSymbol,
+    /// Draw cards can be stacked onto draw cards with an equal or lower draw amount.
+    EqualOrHigher,
+}
+
+/// When a wild draw 4 can be played.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WildDraw4Rule {
+    /// Only legal without a card matching the color of the top card, enforced by the game.
+    Restricted,
+    /// Can always be played, but the next player can challenge a bluff.
+    Challenge,
+    /// Can always be played.
+    Unrestricted,
+}
+
+/// Rules of a game, including house rules.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Rules {
+    /// Number of cards in initial player hands.
+    pub n_initial_cards: usize,
+    /// Composition of the deck.
+    pub deck: DeckSpec,
+    /// How to handle the first card flipped onto the pile.
+    pub first_card: FirstCardRule,
+    /// How many cards to draw when not playing a card.
+    pub draw_mode: DrawMode,
+    /// Card launcher used for draw actions instead of drawing single cards from the deck.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub launcher: Option<Launcher>,
+    /// Whether draw penalties can be stacked.
+    pub stacking: Stacking,
+    /// Whether playing a 7 swaps hands with another player and playing a 0 passes all
+    /// hands on in the direction of play.
+    pub seven_zero: bool,
+    /// Whether players can play an identical card out of turn.
+    pub jump_in: bool,
+    /// When a wild draw 4 can be played.
+    pub wild_draw_4: WildDraw4Rule,
+    /// Whether players sitting opposite each other play as partners, which requires an
+    /// even number of at least 4 players.
+    pub teams: bool,
+    /// Whether partners pass one card to each other at the start of a game.
+    pub partner_pass: bool,
+    /// Number of cards at which a player is eliminated, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mercy_limit: Option<usize>,
+    /// Number of cards drawn when caught not calling "Uno".
+    pub uno_penalty: usize,
+    /// Number of points needed to win a match.
+    pub target_score: u32,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self::official()
+    }
+}
+
+impl Rules {
+    /// Official rules.
+    pub fn official() -> Self {
+        Self {
+            n_initial_cards: N_INITIAL_CARDS,
+            deck: DeckSpec::standard(),
+            first_card: FirstCardRule::Official,
+            draw_mode: DrawMode::DrawOne,
+            launcher: None,
+            stacking: Stacking::Off,
+            seven_zero: false,
+            jump_in: false,
+            wild_draw_4: WildDraw4Rule::Challenge,
+            teams: false,
+            partner_pass: false,
+            mercy_limit: None,
+            uno_penalty: UNO_PENALTY,
+            target_score: TARGET_SCORE,
+        }
+    }
+
+    /// Commonly played house rules, with stacking and drawing until playable.
+    pub fn house_classic() -> Self {
+        Self {
+            first_card: FirstCardRule::Simplified,
+            draw_mode: DrawMode::UntilPlayable,
+            stacking: Stacking::SameSymbol,
+            wild_draw_4: WildDraw4Rule::Unrestricted,
+            ..Self::official()
+        }
+    }
+
+    /// Rules of "Show 'em No Mercy", with its own deck, escalating stacking, 7-0, jump-in
+    /// and players being eliminated when holding 25 cards.
+    pub fn no_mercy() -> Self {
+        Self {
+            deck: DeckSpec::no_mercy(),
+            draw_mode: DrawMode::UntilPlayable,
+            stacking: Stacking::EqualOrHigher,
+            seven_zero: true,
+            jump_in: true,
+            wild_draw_4: WildDraw4Rule::Unrestricted,
+            mercy_limit: Some(MERCY_LIMIT),
+            ..Self::official()
+        }
+    }
+
+    /// Official rules of Uno Flip, played with double-sided cards.
+    pub fn flip() -> Self {
+        Self {
+            deck: DeckSpec::flip(),
+            ..Self::official()
+        }
+    }
+
+    /// Rules of Uno Attack, where draw actions hit the card launcher.
+    pub fn attack() -> Self {
+        Self {
+            launcher: Some(Launcher::default()),
+            ..Self::official()
+        }
+    }
+
+    /// Official rules played in partnership mode, where partners pass each other a card.
+    pub fn partners() -> Self {
+        Self {
+            teams: true,
+            partner_pass: true,
+            ..Self::official()
+        }
+    }
+
+    /// Get rule preset by `name`, see [`PRESETS`].
+    pub fn preset(name: &str) -> Option<Self> {
+        match name {
+            "official" => Some(Self::official()),
+            "house-classic" => Some(Self::house_classic()),
+            "no-mercy" => Some(Self::no_mercy()),
+            "flip" => Some(Self::flip()),
+            "attack" => Some(Self::attack()),
+            "partners" => Some(Self::partners()),
+            _ => None,
+        }
+    }
+
+    /// Get source of cards for draw actions.
+    pub fn draw_source(&self) -> Box<dyn DrawSource> {
+        match &self.launcher {
+            Some(launcher) => Box::new(launcher.clone()),
+            None => Box::new(DeckDraw),
+        }
+    }
+
+    /// Check that the rules can be played by the fewest players they allow, see
+    /// [`Rules::validate_players`].
+    pub fn validate(&self) -> Result<(), RulesError> {
+        self.deck.validate().map_err(RulesError::Invalid)?;
+        if let Some(launcher) = &self.launcher {
+            Launcher::try_new(launcher.weights.clone()).map_err(RulesError::Invalid)?;
+        }
+        if self.n_initial_cards == 0 {
+            return Err(RulesError::Invalid(String::from(
+                "players must be dealt at least one card",
+            )));
+        }
+        if let Some(limit) = self.mercy_limit.filter(|&x| x <= self.n_initial_cards) {
+            return Err(RulesError::Invalid(format!(
+                "mercy limit of {} cards eliminates initial hands of {} cards",
+                limit, self.n_initial_cards
+            )));
+        }
+        let n_players = match self.teams {
+            true => MIN_TEAM_PLAYERS,
+            false => MIN_PLAYERS,
+        };
+        self.validate_players(n_players)
+    }
+
+    /// Check that the deck holds the initial hands of `n_players` and a top card, and
+    /// that they can form teams in partnership mode.
+    pub fn validate_players(&self, n_players: usize) -> Result<(), RulesError> {
+        let n_cards = n_players * self.n_initial_cards + 1;
+        if self.deck.n_cards() < n_cards {
+            return Err(RulesError::Invalid(format!(
+                "deck of {} cards cannot deal {} cards to {} players and a top card",
+                self.deck.n_cards(),
+                self.n_initial_cards,
+                n_players
+            )));
+        }
+        if self.teams && (n_players < MIN_TEAM_PLAYERS || !n_players.is_multiple_of(2)) {
+            return Err(RulesError::Invalid(format!(
+                "partnership mode requires an even number of at least {} players",
+                MIN_TEAM_PLAYERS
+            )));
+        }
+        Ok(())
+    }
+
+    /// Parse rules from TOML string, using official rules for missing fields.
+    pub fn from_toml(s: &str) -> Result<Self, RulesError> {
+        let rules: Self = toml::from_str(s).map_err(RulesError::Parse)?;
+        rules.validate()?;
+        Ok(rules)
+    }
+
+    /// Serialize rules to TOML string.
+    pub fn to_toml(&self) -> String {
+        toml::to_string(self).expect("rules are serializable")
+    }
+
+    /// Load rules from TOML file at `path`.
+    pub fn from_file(path: &Path) -> Result<Self, RulesError> {
+        let s = fs::read_to_string(path).map_err(RulesError::Io)?;
+        Self::from_toml(&s)
+    }
+
+    /// Load rules from preset `name` or, if there is no such preset, from TOML file, see
+    /// [`Rules::validate`].
+    pub fn load(name_or_path: &str) -> Result<Self, RulesError> {
+        match Self::preset(name_or_path) {
+            Some(rules) => Ok(rules),
+            None => Self::from_file(Path::new(name_or_path)),
+        }
+    }
+}
+
+/// Error when loading rules.
+#[derive(Debug)]
+pub enum RulesError {
+    Io(io::Error),
+    Parse(toml::de::Error),
+    Invalid(String),
+}
+
+impl fmt::Display for RulesError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RulesError::Io(error) => write!(f, "cannot read rules: {}", error),
+            RulesError::Parse(error) => write!(f, "cannot parse rules: {}", error),
+            RulesError::Invalid(message) => write!(f, "invalid rules: {}", message),
+        }
+    }
+}
+
+impl Error for RulesError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*; // bring private functions into scope
+    use rstest::rstest;
+
+    #[test]
+    fn test_rules_presets() {
+        for name in PRESETS {
+            assert!(Rules::preset(name).is_some(), "missing preset: {}", name);
+        }
+        assert!(Rules::preset("unknown").is_none());
+    }
+
+    #[test]
+    fn test_rules_toml_roundtrip() {
+        for name in PRESETS {
+            let rules = Rules::preset(name).unwrap();
+            let s = rules.to_toml();
+            assert_eq!(Rules::from_toml(&s).unwrap(), rules);
+        }
+    }
+
+    #[test]
+    fn test_rules_from_toml_partial() {
+        let s = "
+            n_initial_cards = 5
+            stacking = \"same-symbol\"
+            draw_mode = \"until-playable\"
+        ";
+        let rules = Rules::from_toml(s).unwrap();
+        let expected = Rules {
+            n_initial_cards: 5,
+            stacking: Stacking::SameSymbol,
+            draw_mode: DrawMode::UntilPlayable,
+            ..Rules::official()
+        };
+        assert_eq!(rules, expected);
+    }
+
+    #[test]
+    fn test_rules_from_toml_deck() {
+        let s = "
+            [deck]
+            n_decks = 2
+            wilds = 8
+        ";
+        let rules = Rules::from_toml(s).unwrap();
+        let expected = DeckSpec::standard().n_decks(2).wilds(8);
+        assert_eq!(rules.deck, expected);
+    }
+
+    #[test]
+    fn test_rules_from_toml_launcher() {
+        let s = "
+            [launcher]
+            weights = [1, 2, 3]
+        ";
+        let rules = Rules::from_toml(s).unwrap();
+        assert_eq!(rules.launcher, Some(Launcher::new(vec![1, 2, 3])));
+    }
+
+    #[test]
+    fn test_rules_from_toml_invalid() {
+        let s = "stacking = \"always\"";
+        assert!(matches!(Rules::from_toml(s), Err(RulesError::Parse(_))));
+    }
+
+    #[rstest]
+    #[case("n_initial_cards = 60")]
+    #[case("[deck]\nn_colors = 5")]
+    #[case("[deck]\nn_decks = 0")]
+    #[case("[launcher]\nweights = [1]")]
+    #[case("[launcher]\nweights = []")]
+    #[case("unknown_rule = true")]
+    #[case("[deck]\njokers = 2")]
+    #[case("[launcher]\nspeed = 2")]
+    #[case("n_initial_cards = 0")]
+    #[case("mercy_limit = 3")]
+    #[case("mercy_limit = 7")]
+    #[case("teams = true\npartner_pass = true\nn_initial_cards = 0")]
+    #[case("teams = true\nn_initial_cards = 30")]
+    #[case(
+        "[deck]\nnumbers = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0]\nskips = 0\nreverses = 0\ndraw_2s = 0"
+    )]
+    fn test_rules_from_toml_rejected(#[case] s: &str) {
+        assert!(Rules::from_toml(s).is_err(), "accepted: {}", s);
+    }
+
+    #[test]
+    fn test_rules_validate_players() {
+        let rules = Rules::official();
+        assert!(rules.validate().is_ok());
+        assert!(rules.validate_players(15).is_ok());
+        assert!(matches!(
+            rules.validate_players(16),
+            Err(RulesError::Invalid(_))
+        ));
+    }
+
+    #[rstest]
+    #[case(2, false)]
+    #[case(3, false)]
+    #[case(4, true)]
+    #[case(5, false)]
+    #[case(6, true)]
+    fn test_rules_validate_players_teams(#[case] n_players: usize, #[case] is_valid: bool) {
+        let rules = Rules::partners();
+        assert_eq!(rules.validate_players(n_players).is_ok(), is_valid);
+    }
+}
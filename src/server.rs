@@ -0,0 +1,1588 @@
+use crate::bot::{find_play, fmt_token, parse_color};
+use crate::web;
+use crate::{create_strategy, is_verbose, set_verbose, Card, Cards, Color, Direction};
+use crate::{
+    Game, GameResult, GameStats, Play, PlayerView, Ratings, Rules, Side, Strategy, PLAYER_NAMES,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+const TIMEOUT: Duration = Duration::from_secs(30); // default time to answer a request
+const BOT: &str = "save-wilds"; // default strategy of empty seats
+const EXPIRY: Duration = Duration::from_secs(10 * 60); // default time before closing abandoned tables
+
+/// Rules of a new table, given by preset name or in full.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum RulesSpec {
+    Preset(String),
+    Rules(Box<Rules>),
+}
+
+/// Message sent by a client, one JSON object per line.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum ClientMessage {
+    CreateTable {
+        n_players: Option<usize>,
+        rules: Option<RulesSpec>,
+    },
+    ListTables,
+    Join {
+        name: String,
+        table: Option<u64>,
+    },
+    Rejoin {
+        token: String,
+    },
+    Spectate {
+        table: u64,
+    },
+    Leave,
+    Start,
+    Play {
+        id: u64,
+        card: String,
+    },
+    Draw {
+        id: u64,
+    },
+    Color {
+        id: u64,
+        color: String,
+    },
+}
+
+impl ClientMessage {
+    /// Get id of the request answered by the message, if any.
+    fn id(&self) -> Option<u64> {
+        match self {
+            Self::Play { id, .. } | Self::Draw { id } | Self::Color { id, .. } => Some(*id),
+            _ => None,
+        }
+    }
+}
+
+/// Message sent by the server, one JSON object per line.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum ServerMessage {
+    TableCreated {
+        table: u64,
+    },
+    Tables {
+        tables: Vec<TableInfo>,
+    },
+    Seated {
+        table: u64,
+        seat: usize,
+        token: String,
+        n_players: usize,
+        rules: Rules,
+    },
+    Spectating {
+        table: u64,
+        n_players: usize,
+        rules: Rules,
+    },
+    Left {
+        table: u64,
+    },
+    Players {
+        names: Vec<Option<String>>,
+    },
+    Started {
+        game: u64,
+    },
+    View {
+        view: ViewMessage,
+    },
+    Turn {
+        id: u64,
+        view: ViewMessage,
+        legal: Vec<String>,
+    },
+    ChooseColor {
+        id: u64,
+        hand: Vec<String>,
+        side: String,
+    },
+    Timeout {
+        id: u64,
+    },
+    GameOver {
+        winner: usize,
+        team: Option<usize>,
+        points: u32,
+        placements: Vec<usize>,
+        stats: GameStats,
+    },
+    Error {
+        message: String,
+    },
+}
+
+/// Summary of a table listed in the lobby.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct TableInfo {
+    table: u64,
+    n_players: usize,
+    rules: Rules,
+    players: Vec<Option<String>>,
+    n_spectators: usize,
+    is_playing: bool,
+}
+
+/// View of the game sent to a single player, with cards written as protocol tokens.
+/// Spectators receive the view without player and hand.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct ViewMessage {
+    player: Option<usize>,
+    next: usize,
+    direction: String,
+    pending_draw: usize,
+    top: String,
+    hand: Vec<String>,
+    hand_sizes: Vec<usize>,
+    teams: Option<Vec<usize>>,
+    deck: usize,
+    pile: Vec<String>,
+}
+
+impl ViewMessage {
+    fn new(view: &PlayerView) -> Self {
+        let direction = match view.direction {
+            Direction::Forward => "forward",
+            Direction::Reverse => "reverse",
+        };
+        let n_other_cards = view.hand_sizes.iter().sum::<usize>() - view.hand.len();
+        Self {
+            player: Some(view.index),
+            next: view.next,
+            direction: direction.to_string(),
+            pending_draw: view.pending_draw,
+            top: fmt_token(&view.top_card),
+            hand: tokens(&view.hand),
+            hand_sizes: view.hand_sizes.clone(),
+            teams: view.teams.clone(),
+            deck: view.unknown_cards.len() - n_other_cards,
+            pile: tokens(&view.pile),
+        }
+    }
+
+    /// Remove the player and their hand from the view.
+    fn public(mut self) -> Self {
+        self.player = None;
+        self.hand.clear();
+        self
+    }
+}
+
+/// Format `cards` as list of protocol tokens.
+fn tokens(cards: &[Card]) -> Vec<String> {
+    cards.iter().map(fmt_token).collect()
+}
+
+/// Check if a table for `n_players` with `rules` can be played with `bot` strategies.
+fn validate(n_players: usize, rules: &Rules, bot: &str) -> Result<(), String> {
+    if !(2..=PLAYER_NAMES.len()).contains(&n_players) {
+        Err(format!("unsupported number of players: {}", n_players))
+    } else if let Err(error) = rules
+        .validate()
+        .and_then(|_| rules.validate_players(n_players))
+    {
+        Err(error.to_string())
+    } else if create_strategy(bot, rules).is_none() {
+        Err(format!("unknown bot: {}", bot))
+    } else {
+        Ok(())
+    }
+}
+
+/// Answer of a player to a request, or notice that the player disconnected.
+enum Reply {
+    Answer(ClientMessage),
+    Disconnected,
+}
+
+/// Connection of a client, given by its id and the sender of its messages.
+type Connection = (u64, Sender<ServerMessage>);
+
+/// Seat at a table, taken by a player or played by a bot while empty or disconnected.
+#[derive(Default)]
+struct Seat {
+    name: Option<String>,
+    token: Option<String>,
+    connection: Option<Connection>,
+    view: Option<ViewMessage>,
+}
+
+/// State of a table shared between its connections and the thread playing its games.
+#[derive(Default)]
+struct TableState {
+    seats: Vec<Seat>,
+    spectators: Vec<Connection>,
+    view: Option<ViewMessage>,
+    is_playing: bool,
+    starts: Option<Sender<()>>,
+    idle_since: Option<Instant>,
+}
+
+impl TableState {
+    /// Get senders of all connected players and spectators.
+    fn outboxes(&self) -> impl Iterator<Item = &Sender<ServerMessage>> {
+        let seats = self.seats.iter().filter_map(|x| x.connection.as_ref());
+        seats.chain(&self.spectators).map(|(_, outbox)| outbox)
+    }
+
+    /// Remember when the last client disconnected, if nobody is connected anymore.
+    fn update_idle(&mut self) {
+        if self.outboxes().next().is_none() {
+            self.idle_since = Some(Instant::now());
+        }
+    }
+}
+
+/// Table hosting a series of games, where each seat sees only its own view.
+pub(crate) struct Table {
+    id: u64,
+    n_players: usize,
+    rules: Rules,
+    timeout: Duration,
+    bot: String,
+    seed: Option<u64>,
+    log: Option<Mutex<File>>,
+    ratings: Option<Arc<RatingsFile>>,
+    state: Mutex<TableState>,
+    replies: Vec<Mutex<Receiver<Reply>>>,
+    repliers: Vec<Sender<Reply>>,
+    next_id: AtomicU64,
+}
+
+impl Table {
+    /// Open table with `id` for `n_players` playing with `rules` and start the thread
+    /// playing its games whenever a player starts one.
+    fn open(
+        id: u64,
+        n_players: usize,
+        rules: Rules,
+        settings: &Settings,
+    ) -> Result<Arc<Self>, String> {
+        validate(n_players, &rules, &settings.bot)?;
+        let log = match &settings.log_dir {
+            Some(dir) => {
+                let path = dir.join(format!("table-{}.jsonl", id));
+                let file = File::options().create(true).append(true).open(&path);
+                let file =
+                    file.map_err(|error| format!("cannot open {}: {}", path.display(), error))?;
+                Some(Mutex::new(file))
+            }
+            None => None,
+        };
+        let (repliers, replies) = (0..n_players)
+            .map(|_| {
+                let (sender, receiver) = mpsc::channel();
+                (sender, Mutex::new(receiver))
+            })
+            .unzip();
+        let (starts, start_receiver) = mpsc::channel();
+        let state = TableState {
+            seats: (0..n_players).map(|_| Seat::default()).collect(),
+            starts: Some(starts),
+            ..TableState::default()
+        };
+        let table = Arc::new(Self {
+            id,
+            n_players,
+            rules,
+            timeout: settings.timeout,
+            bot: settings.bot.clone(),
+            seed: settings.seed,
+            log,
+            ratings: settings.ratings.clone(),
+            state: Mutex::new(state),
+            replies,
+            repliers,
+            next_id: AtomicU64::new(0),
+        });
+        table.log(json!({"event": "table_opened", "n_players": n_players, "rules": table.rules}));
+
+        let verbose = is_verbose();
+        let cloned = table.clone();
+        thread::spawn(move || {
+            set_verbose(verbose);
+            cloned.play_games(start_receiver);
+        });
+        Ok(table)
+    }
+
+    /// Play a game each time one is started, until the table is closed.
+    fn play_games(self: Arc<Self>, starts: Receiver<()>) {
+        for n_games in 0.. {
+            if starts.recv().is_err() {
+                return;
+            }
+            let seed = match self.seed {
+                Some(seed) => seed.wrapping_add(n_games),
+                None => rand::random(),
+            };
+            let dealer = n_games as usize % self.n_players;
+            let mut game = Game::with_seed(self.n_players, dealer, self.rules.clone(), seed);
+            for index in 0..self.n_players {
+                let bot = create_strategy(&self.bot, &self.rules).expect("known strategy");
+                let strategy = RemoteStrategy {
+                    table: self.clone(),
+                    index,
+                    bot,
+                };
+                game.set_strategy(index, Box::new(strategy));
+            }
+            self.broadcast(ServerMessage::Started { game: n_games });
+            self.log(
+                json!({"event": "game_started", "game": n_games, "seed": seed, "dealer": dealer}),
+            );
+            game.start();
+            self.update_views(&game);
+
+            let mut n_events = 0;
+            let result = loop {
+                let player = game.players.cycle.peek_next();
+                let result = game.step();
+                self.update_views(&game);
+                self.log(json!({
+                    "event": "turn",
+                    "game": n_games,
+                    "player": player,
+                    "top": fmt_token(&game.top_card()),
+                    "hand_sizes": game.players.hand_sizes(),
+                }));
+                for event in &game.events()[n_events..] {
+                    let mut event = serde_json::to_value(event).expect("events are serializable");
+                    event["game"] = json!(n_games);
+                    self.log(event);
+                }
+                n_events = game.events().len();
+                if let Some(result) = result {
+                    break result;
+                }
+            };
+            let stats = GameStats::from_events(self.n_players, game.events());
+            self.finish(n_games, result, stats);
+        }
+    }
+
+    /// Append `event` to the log of the table, if any, with the time and table id.
+    fn log(&self, event: Value) {
+        let (Some(file), Value::Object(fields)) = (&self.log, event) else {
+            return;
+        };
+        let time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |x| x.as_millis());
+        let mut line = json!({"time": time, "table": self.id});
+        line.as_object_mut().expect("object").extend(fields);
+        let mut file = file.lock().expect("log lock");
+        if let Err(error) = writeln!(file, "{}", line) {
+            log!("Table: {} cannot write log: {}", self.id, error);
+        }
+    }
+
+    /// Send the current view to each seat and the public view to spectators.
+    fn update_views(&self, game: &Game) {
+        let mut state = self.state.lock().expect("table lock");
+        for (index, seat) in state.seats.iter_mut().enumerate() {
+            let view = ViewMessage::new(&game.view(index));
+            if let Some((_, outbox)) = &seat.connection {
+                let _ = outbox.send(ServerMessage::View { view: view.clone() });
+            }
+            seat.view = Some(view);
+        }
+        let view = ViewMessage::new(&game.view(0)).public();
+        for (_, outbox) in &state.spectators {
+            let _ = outbox.send(ServerMessage::View { view: view.clone() });
+        }
+        state.view = Some(view);
+    }
+
+    /// Announce `result` and `stats` of game `n_games` and wait for the next game to be
+    /// started.
+    fn finish(&self, n_games: u64, result: GameResult, stats: GameStats) {
+        self.log(json!({
+            "event": "game_over",
+            "game": n_games,
+            "winner": result.winner,
+            "team": result.team,
+            "points": result.points,
+            "placements": result.placements,
+            "stats": stats,
+        }));
+        let (names, placements) = self.rated_players(&result.placements);
+        if let (Some(ratings), true) = (&self.ratings, names.len() > 1) {
+            ratings.update(&names, &placements);
+        }
+        self.broadcast(ServerMessage::GameOver {
+            winner: result.winner,
+            team: result.team,
+            points: result.points,
+            placements: result.placements,
+            stats,
+        });
+        self.state.lock().expect("table lock").is_playing = false;
+    }
+
+    /// Get names of the players in taken seats and their order in `placements`, leaving
+    /// out empty seats played by the bot.
+    fn rated_players(&self, placements: &[usize]) -> (Vec<String>, Vec<usize>) {
+        let state = self.state.lock().expect("table lock");
+        let seats: Vec<usize> = (0..self.n_players)
+            .filter(|&x| state.seats[x].name.is_some())
+            .collect();
+        let names = seats
+            .iter()
+            .filter_map(|&x| state.seats[x].name.clone())
+            .collect();
+        let placements = placements
+            .iter()
+            .filter_map(|x| seats.iter().position(|y| y == x))
+            .collect();
+        (names, placements)
+    }
+
+    /// Send `message` to all connected seats and spectators.
+    fn broadcast(&self, message: ServerMessage) {
+        let state = self.state.lock().expect("table lock");
+        for outbox in state.outboxes() {
+            let _ = outbox.send(message.clone());
+        }
+    }
+
+    /// Send `message` to seat at `index`, returning whether it is connected.
+    fn send(&self, index: usize, message: ServerMessage) -> bool {
+        let state = self.state.lock().expect("table lock");
+        match &state.seats[index].connection {
+            Some((_, outbox)) => outbox.send(message).is_ok(),
+            None => false,
+        }
+    }
+
+    /// Send names of the players at the table to all connected seats and spectators.
+    fn broadcast_players(&self) {
+        let names = {
+            let state = self.state.lock().expect("table lock");
+            state.seats.iter().map(|x| x.name.clone()).collect()
+        };
+        self.broadcast(ServerMessage::Players { names });
+    }
+
+    /// Get summary of the table.
+    fn info(&self) -> TableInfo {
+        let state = self.state.lock().expect("table lock");
+        TableInfo {
+            table: self.id,
+            n_players: self.n_players,
+            rules: self.rules.clone(),
+            players: state.seats.iter().map(|x| x.name.clone()).collect(),
+            n_spectators: state.spectators.len(),
+            is_playing: state.is_playing,
+        }
+    }
+
+    /// Seat player with `name` at the first free seat of the table, connected by
+    /// `connection`, returning the seat.
+    fn join(&self, name: String, connection: Connection) -> Result<usize, String> {
+        let index = {
+            let mut state = self.state.lock().expect("table lock");
+            if state.starts.is_none() {
+                return Err(String::from("table is closed"));
+            }
+            if state.seats.iter().any(|x| x.name.as_ref() == Some(&name)) {
+                return Err(format!("name {} is taken", name));
+            }
+            let index = state
+                .seats
+                .iter()
+                .position(|x| x.name.is_none())
+                .ok_or("table is full")?;
+            let seat = &mut state.seats[index];
+            seat.name = Some(name.clone());
+            seat.token = Some(format!("{:016x}", rand::random::<u64>()));
+            seat.connection = Some(connection);
+            index
+        };
+        self.log(json!({"event": "joined", "seat": index, "name": name}));
+        self.seat(index);
+        Ok(index)
+    }
+
+    /// Reconnect player holding `token` by `connection`, returning the seat, if the
+    /// token belongs to the table.
+    fn rejoin(&self, token: &str, connection: Connection) -> Option<usize> {
+        let index = {
+            let mut state = self.state.lock().expect("table lock");
+            let index = state
+                .seats
+                .iter()
+                .position(|x| x.token.as_deref() == Some(token))?;
+            state.seats[index].connection = Some(connection);
+            index
+        };
+        self.log(json!({"event": "rejoined", "seat": index}));
+        self.seat(index);
+        Some(index)
+    }
+
+    /// Tell seat at `index` where it is seated and show its current view, if any.
+    fn seat(&self, index: usize) {
+        let (token, view) = {
+            let state = self.state.lock().expect("table lock");
+            let seat = &state.seats[index];
+            (seat.token.clone().expect("taken seat"), seat.view.clone())
+        };
+        self.send(
+            index,
+            ServerMessage::Seated {
+                table: self.id,
+                seat: index,
+                token,
+                n_players: self.n_players,
+                rules: self.rules.clone(),
+            },
+        );
+        if let Some(view) = view {
+            self.send(index, ServerMessage::View { view });
+        }
+        self.broadcast_players();
+    }
+
+    /// Add spectator connected by `connection`, showing the public view, if any.
+    fn spectate(&self, connection: Connection) -> Result<(), String> {
+        {
+            let mut state = self.state.lock().expect("table lock");
+            if state.starts.is_none() {
+                return Err(String::from("table is closed"));
+            }
+            let outbox = &connection.1;
+            let _ = outbox.send(ServerMessage::Spectating {
+                table: self.id,
+                n_players: self.n_players,
+                rules: self.rules.clone(),
+            });
+            if let Some(view) = state.view.clone() {
+                let _ = outbox.send(ServerMessage::View { view });
+            }
+            state.spectators.push(connection);
+        }
+        self.broadcast_players();
+        Ok(())
+    }
+
+    /// Remove spectator connected by `connection`.
+    fn remove_spectator(&self, connection: u64) {
+        let mut state = self.state.lock().expect("table lock");
+        state.spectators.retain(|x| x.0 != connection);
+        state.update_idle();
+    }
+
+    /// Disconnect `connection` from seat at `index`, keeping the seat for reconnecting.
+    fn disconnect(&self, index: usize, connection: u64) {
+        let mut state = self.state.lock().expect("table lock");
+        let seat = &mut state.seats[index];
+        if seat.connection.as_ref().is_some_and(|x| x.0 == connection) {
+            seat.connection = None;
+            let _ = self.repliers[index].send(Reply::Disconnected);
+            state.update_idle();
+            drop(state);
+            self.log(json!({"event": "disconnected", "seat": index}));
+        }
+    }
+
+    /// Free seat at `index` taken by `connection`, leaving it to the bot.
+    fn leave(&self, index: usize, connection: u64) {
+        {
+            let mut state = self.state.lock().expect("table lock");
+            let seat = &mut state.seats[index];
+            if seat.connection.as_ref().is_none_or(|x| x.0 != connection) {
+                return;
+            }
+            *seat = Seat::default();
+            let _ = self.repliers[index].send(Reply::Disconnected);
+            state.update_idle();
+        }
+        self.log(json!({"event": "left", "seat": index}));
+        self.broadcast_players();
+    }
+
+    /// Check if no seat is taken and nobody is watching.
+    fn is_empty(&self) -> bool {
+        let state = self.state.lock().expect("table lock");
+        state.seats.iter().all(|x| x.name.is_none()) && state.spectators.is_empty()
+    }
+
+    /// Get time since the last client disconnected, if nobody is connected.
+    fn idle_time(&self) -> Option<Duration> {
+        let state = self.state.lock().expect("table lock");
+        let is_connected = state.outboxes().next().is_some();
+        state
+            .idle_since
+            .filter(|_| !is_connected)
+            .map(|x| x.elapsed())
+    }
+
+    /// Close table, stopping its thread after the current game.
+    fn close(&self) {
+        self.state.lock().expect("table lock").starts = None;
+        self.log(json!({"event": "table_closed"}));
+    }
+
+    /// Start the next game, filling empty seats with bots.
+    fn start(&self) -> Result<(), String> {
+        let mut state = self.state.lock().expect("table lock");
+        if state.is_playing {
+            return Err(String::from("game is running"));
+        }
+        let starts = state.starts.as_ref().ok_or("table is closed")?;
+        starts
+            .send(())
+            .map_err(|_| String::from("table is closed"))?;
+        state.is_playing = true;
+        Ok(())
+    }
+}
+
+/// Strategy of a seat, asking the connected player and falling back to the bot for
+/// empty seats, disconnected players, timeouts and illegal answers.
+struct RemoteStrategy {
+    table: Arc<Table>,
+    index: usize,
+    bot: Box<dyn Strategy>,
+}
+
+impl RemoteStrategy {
+    /// Send request created by `message` from a new id to the player and wait for the
+    /// answer, if connected.
+    fn ask(&self, message: impl FnOnce(u64) -> ServerMessage) -> Option<ClientMessage> {
+        let replies = self.table.replies[self.index].lock().expect("replies lock");
+        // ignore answers to previous requests
+        while replies.try_recv().is_ok() {}
+
+        let id = self.table.next_id.fetch_add(1, Ordering::Relaxed);
+        if !self.table.send(self.index, message(id)) {
+            return None;
+        }
+        let deadline = Instant::now() + self.table.timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match replies.recv_timeout(remaining) {
+                Ok(Reply::Answer(answer)) if answer.id() == Some(id) => return Some(answer),
+                Ok(Reply::Answer(_)) => continue,
+                Ok(Reply::Disconnected) => return None,
+                Err(RecvTimeoutError::Timeout) => {
+                    log!("Seat: {} timed out", self.index);
+                    self.table
+                        .log(json!({"event": "timeout", "seat": self.index}));
+                    self.table.send(self.index, ServerMessage::Timeout { id });
+                    return None;
+                }
+                Err(RecvTimeoutError::Disconnected) => return None,
+            }
+        }
+    }
+}
+
+impl Strategy for RemoteStrategy {
+    fn select_card(&self, legal_cards: Cards) -> Play {
+        self.bot.select_card(legal_cards)
+    }
+
+    fn select_card_with_view(&self, legal_cards: Cards, view: &PlayerView) -> Play {
+        let answer = self.ask(|id| ServerMessage::Turn {
+            id,
+            view: ViewMessage::new(view),
+            legal: tokens(&legal_cards),
+        });
+        let play = match &answer {
+            Some(ClientMessage::Draw { .. }) => return None,
+            Some(ClientMessage::Play { card, .. }) => find_play(&legal_cards, card, view),
+            _ => None,
+        };
+        play.or_else(|| {
+            if answer.is_some() {
+                log!("Seat: {} illegal answer: {:?}", self.index, answer);
+                self.table.send(
+                    self.index,
+                    ServerMessage::Error {
+                        message: String::from("illegal play"),
+                    },
+                );
+            }
+            self.bot.select_card_with_view(legal_cards, view)
+        })
+    }
+
+    fn select_color(&self, hand: &Cards, side: Side) -> Color {
+        let side_name = match side {
+            Side::Light => "light",
+            Side::Dark => "dark",
+        };
+        let answer = self.ask(|id| ServerMessage::ChooseColor {
+            id,
+            hand: tokens(hand),
+            side: side_name.to_string(),
+        });
+        let color = match answer {
+            Some(ClientMessage::Color { color, .. }) => parse_color(&color),
+            _ => None,
+        };
+        match color.filter(|x| side.colors().contains(x)) {
+            Some(color) => color,
+            None => self.bot.select_color(hand, side),
+        }
+    }
+
+    fn select_pass_card(&self, hand: &Cards) -> Card {
+        self.bot.select_pass_card(hand)
+    }
+
+    fn select_swap_player(&self, hand_sizes: &[usize], index: usize) -> usize {
+        self.bot.select_swap_player(hand_sizes, index)
+    }
+
+    fn jump_in(&self, card: &Card) -> bool {
+        self.bot.jump_in(card)
+    }
+
+    fn challenge_wild_draw_4(&self) -> bool {
+        self.bot.challenge_wild_draw_4()
+    }
+
+    fn call_uno(&self) -> bool {
+        self.bot.call_uno()
+    }
+
+    fn catch_uno(&self) -> bool {
+        self.bot.catch_uno()
+    }
+}
+
+/// Settings of the server, where the number of players and the rules are the defaults
+/// of new tables.
+#[derive(Debug, Clone)]
+struct Settings {
+    n_players: usize,
+    rules: Rules,
+    timeout: Duration,
+    bot: String,
+    seed: Option<u64>,
+    log_dir: Option<PathBuf>,
+    ratings: Option<Arc<RatingsFile>>,
+    expiry: Duration,
+}
+
+/// Ratings shared by all tables and saved to their file after each game.
+#[derive(Debug)]
+struct RatingsFile {
+    path: PathBuf,
+    ratings: Mutex<Ratings>,
+}
+
+impl RatingsFile {
+    /// Load ratings from file at `path`.
+    fn load(path: &Path) -> io::Result<Self> {
+        Ok(Self {
+            path: path.to_path_buf(),
+            ratings: Mutex::new(Ratings::load(path)?),
+        })
+    }
+
+    /// Update ratings of players with `names` from `placements` and save them.
+    fn update(&self, names: &[String], placements: &[usize]) {
+        let mut ratings = self.ratings.lock().expect("ratings lock");
+        ratings.update(names, placements);
+        if let Err(error) = ratings.save(&self.path) {
+            log!("Cannot save ratings to {}: {}", self.path.display(), error);
+        }
+    }
+}
+
+/// Lobby of all open tables.
+pub(crate) struct Lobby {
+    settings: Settings,
+    tables: Mutex<BTreeMap<u64, Arc<Table>>>,
+    next_id: AtomicU64,
+}
+
+impl Lobby {
+    fn new(settings: Settings) -> Self {
+        Self {
+            settings,
+            tables: Mutex::new(BTreeMap::new()),
+            next_id: AtomicU64::new(0),
+        }
+    }
+
+    /// Open table for `n_players` with `rules`, defaulting to the settings.
+    fn create(
+        &self,
+        n_players: Option<usize>,
+        rules: Option<RulesSpec>,
+    ) -> Result<Arc<Table>, String> {
+        let rules = match rules {
+            Some(RulesSpec::Preset(name)) => {
+                Rules::preset(&name).ok_or_else(|| format!("unknown rules preset: {}", name))?
+            }
+            Some(RulesSpec::Rules(rules)) => *rules,
+            None => self.settings.rules.clone(),
+        };
+        let n_players = n_players.unwrap_or(self.settings.n_players);
+        let mut tables = self.tables.lock().expect("lobby lock");
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let table = Table::open(id, n_players, rules, &self.settings)?;
+        tables.insert(id, table.clone());
+        Ok(table)
+    }
+
+    /// Get table with `id`, or the first table if not given, opening a table with the
+    /// default settings if there is none.
+    fn table(&self, id: Option<u64>) -> Result<Arc<Table>, String> {
+        let tables = self.tables.lock().expect("lobby lock");
+        let table = match id {
+            Some(id) => tables.get(&id),
+            None => tables.values().next(),
+        };
+        match (table, id) {
+            (Some(table), _) => Ok(table.clone()),
+            (None, Some(id)) => Err(format!("unknown table: {}", id)),
+            (None, None) => {
+                drop(tables);
+                self.create(None, None)
+            }
+        }
+    }
+
+    /// List summaries of all open tables.
+    fn list(&self) -> Vec<TableInfo> {
+        let tables = self.tables.lock().expect("lobby lock");
+        tables.values().map(|x| x.info()).collect()
+    }
+
+    /// Reconnect player holding `token` by `connection` to their table and seat.
+    fn rejoin(&self, token: &str, connection: Connection) -> Result<(Arc<Table>, usize), String> {
+        let tables: Vec<_> = self
+            .tables
+            .lock()
+            .expect("lobby lock")
+            .values()
+            .cloned()
+            .collect();
+        tables
+            .into_iter()
+            .find_map(|table| {
+                let index = table.rejoin(token, connection.clone())?;
+                Some((table, index))
+            })
+            .ok_or_else(|| String::from("unknown token"))
+    }
+
+    /// Close and remove `table` if it is empty, or once the expiry has passed if
+    /// nobody is connected to it.
+    fn clean_up(self: &Arc<Self>, table: &Arc<Table>) {
+        let mut tables = self.tables.lock().expect("lobby lock");
+        if table.is_empty() {
+            if tables.remove(&table.id).is_some() {
+                table.close();
+            }
+        } else if table.idle_time().is_some() {
+            let (lobby, table) = (self.clone(), table.clone());
+            thread::spawn(move || {
+                thread::sleep(lobby.settings.expiry);
+                lobby.expire(&table);
+            });
+        }
+    }
+
+    /// Close and remove `table` if nobody has been connected to it since the expiry.
+    fn expire(&self, table: &Table) {
+        let mut tables = self.tables.lock().expect("lobby lock");
+        let is_expired = table.idle_time().is_some_and(|x| x >= self.settings.expiry);
+        if is_expired && tables.remove(&table.id).is_some() {
+            table.close();
+        }
+    }
+}
+
+/// Role of a client at a table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Role {
+    Player(usize),
+    Spectator,
+}
+
+/// Connection of a client to the lobby, independent of the transport carrying the
+/// messages.
+pub(crate) struct Session {
+    lobby: Arc<Lobby>,
+    id: u64,
+    outbox: Sender<ServerMessage>,
+    table: Option<(Arc<Table>, Role)>,
+}
+
+impl Session {
+    /// Create session in `lobby` sending messages for the client to `outbox`.
+    pub(crate) fn new(lobby: Arc<Lobby>, outbox: Sender<ServerMessage>) -> Self {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+        Self {
+            lobby,
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            outbox,
+            table: None,
+        }
+    }
+
+    /// Handle `line` received from the client.
+    pub(crate) fn receive(&mut self, line: &str) {
+        let message = match serde_json::from_str::<ClientMessage>(line) {
+            Ok(message) => message,
+            Err(error) => return self.error(format!("invalid message: {}", error)),
+        };
+        if let Err(message) = self.handle(message) {
+            self.error(message);
+        }
+    }
+
+    /// Handle `message` received from the client.
+    fn handle(&mut self, message: ClientMessage) -> Result<(), String> {
+        let role = self.table.as_ref().map(|x| x.1);
+        match (message, role) {
+            (ClientMessage::CreateTable { n_players, rules }, _) => {
+                let table = self.lobby.create(n_players, rules)?;
+                self.send(ServerMessage::TableCreated { table: table.id });
+            }
+            (ClientMessage::ListTables, _) => {
+                let tables = self.lobby.list();
+                self.send(ServerMessage::Tables { tables });
+            }
+            (
+                ClientMessage::Join { .. }
+                | ClientMessage::Rejoin { .. }
+                | ClientMessage::Spectate { .. },
+                Some(_),
+            ) => return Err(String::from("already at a table")),
+            (ClientMessage::Join { name, table }, None) => {
+                let table = self.lobby.table(table)?;
+                let index = table.join(name, self.connection())?;
+                self.table = Some((table, Role::Player(index)));
+            }
+            (ClientMessage::Rejoin { token }, None) => {
+                let (table, index) = self.lobby.rejoin(&token, self.connection())?;
+                self.table = Some((table, Role::Player(index)));
+            }
+            (ClientMessage::Spectate { table }, None) => {
+                let table = self.lobby.table(Some(table))?;
+                table.spectate(self.connection())?;
+                self.table = Some((table, Role::Spectator));
+            }
+            (ClientMessage::Leave, _) => {
+                let (table, role) = self.table.take().ok_or("not at a table")?;
+                match role {
+                    Role::Player(index) => table.leave(index, self.id),
+                    Role::Spectator => table.remove_spectator(self.id),
+                }
+                self.send(ServerMessage::Left { table: table.id });
+                self.lobby.clean_up(&table);
+            }
+            (_, None | Some(Role::Spectator)) => return Err(String::from("not seated")),
+            (ClientMessage::Start, Some(Role::Player(_))) => {
+                let (table, _) = self.table.as_ref().expect("seated");
+                table.start()?;
+            }
+            (answer, Some(Role::Player(index))) => {
+                let (table, _) = self.table.as_ref().expect("seated");
+                let _ = table.repliers[index].send(Reply::Answer(answer));
+            }
+        }
+        Ok(())
+    }
+
+    /// Get connection of the session for joining a table.
+    fn connection(&self) -> Connection {
+        (self.id, self.outbox.clone())
+    }
+
+    /// Send `message` to the client.
+    fn send(&self, message: ServerMessage) {
+        let _ = self.outbox.send(message);
+    }
+
+    /// Send error `message` to the client.
+    fn error(&self, message: String) {
+        self.send(ServerMessage::Error { message });
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        match self.table.take() {
+            Some((table, Role::Player(index))) => {
+                table.disconnect(index, self.id);
+                self.lobby.clean_up(&table);
+            }
+            Some((table, Role::Spectator)) => {
+                table.remove_spectator(self.id);
+                self.lobby.clean_up(&table);
+            }
+            None => (),
+        }
+    }
+}
+
+/// Server hosting a lobby of tables over TCP, where each client takes a seat at a table
+/// and empty seats are played by bots.
+///
+/// Clients and server exchange JSON objects, one per line, tagged by `type`. Cards are
+/// written as protocol tokens like for [`ProcessStrategy`](crate::ProcessStrategy), e.g.
+/// `7:Red`, `Skip:Blue` or `WildDraw4`.
+///
+/// Client to server:
+/// - `{"type": "create_table", "n_players": <n>, "rules": <preset>|{..}}`: open a
+///   table, with the number of players and the rules of the server by default.
+/// - `{"type": "list_tables"}`
+/// - `{"type": "join", "name": <name>, "table": <table>}`: take the first free seat at
+///   the table, or at the first table if not given.
+/// - `{"type": "rejoin", "token": <token>}`: reconnect to the seat given by the token.
+/// - `{"type": "spectate", "table": <table>}`: watch the table without seeing any hand.
+/// - `{"type": "leave"}`: give up the seat or stop watching, where tables are closed
+///   when nobody is left.
+/// - `{"type": "start"}`: start the next game, filling empty seats with bots.
+/// - `{"type": "play", "id": <id>, "card": <card>}`: play one of the legal cards of
+///   turn `id`, choosing the color of wild cards, e.g. `Wild:Green`.
+/// - `{"type": "draw", "id": <id>}`: draw instead of playing in turn `id`.
+/// - `{"type": "color", "id": <id>, "color": <color>}`: choose the color of a wild card.
+///
+/// Server to client:
+/// - `{"type": "table_created", "table": <table>}`
+/// - `{"type": "tables", "tables": [{"table": <table>, "n_players": <n>, "rules": {..},
+///   "players": [<name>|null, ..], "n_spectators": <n>, "is_playing": <bool>}, ..]}`
+/// - `{"type": "seated", "table": <table>, "seat": <i>, "token": <token>,
+///   "n_players": <n>, "rules": {..}}`
+/// - `{"type": "spectating", "table": <table>, "n_players": <n>, "rules": {..}}`
+/// - `{"type": "left", "table": <table>}`
+/// - `{"type": "players", "names": [<name>|null, ..]}`: players at the table.
+/// - `{"type": "started", "game": <n>}`
+/// - `{"type": "view", "view": <view>}`: view of the player after each turn, with fields
+///   `player`, `next`, `direction`, `pending_draw`, `top`, `hand`, `hand_sizes`, `teams`,
+///   `deck` and `pile`, where spectators see no player and hand.
+/// - `{"type": "turn", "id": <id>, "view": <view>, "legal": [<card>, ..]}`: select a card.
+/// - `{"type": "choose_color", "id": <id>, "hand": [<card>, ..], "side": "light"|"dark"}`
+/// - `{"type": "timeout", "id": <id>}`: the bot decided as no answer arrived in time.
+/// - `{"type": "game_over", "winner": <i>, "team": <t>|null, "points": <n>,
+///   "placements": [<i>, ..], "stats": <stats>}`: result and statistics of the game, see
+///   [`GameStats`].
+/// - `{"type": "error", "message": <message>}`
+///
+/// The bot also decides for disconnected players, who keep their seat and can rejoin
+/// with their token, and in place of illegal answers. Tables nobody is connected to are
+/// closed after the expiry, freeing the seats of disconnected players. Given a log
+/// directory, each table appends its events to `table-<id>.jsonl`, one JSON object per
+/// line. Given a ratings file, the ratings of the players by name are updated after each
+/// game with at least two of them, leaving out the bot in empty seats.
+pub struct Server {
+    settings: Settings,
+    web: Option<TcpListener>,
+    ratings: Option<PathBuf>,
+}
+
+impl Server {
+    /// Create server for tables of `n_players` playing with `rules` by default.
+    pub fn new(n_players: usize, rules: Rules) -> Self {
+        let settings = Settings {
+            n_players,
+            rules,
+            timeout: TIMEOUT,
+            bot: String::from(BOT),
+            seed: None,
+            log_dir: None,
+            ratings: None,
+            expiry: EXPIRY,
+        };
+        Self {
+            settings,
+            web: None,
+            ratings: None,
+        }
+    }
+
+    /// Set time players have to answer a request.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.settings.timeout = timeout;
+        self
+    }
+
+    /// Set time after which tables nobody is connected to are closed.
+    pub fn expiry(mut self, expiry: Duration) -> Self {
+        self.settings.expiry = expiry;
+        self
+    }
+
+    /// Set strategy spec of the bots filling empty seats, see [`create_strategy`].
+    pub fn bot(mut self, bot: &str) -> Self {
+        self.settings.bot = bot.to_string();
+        self
+    }
+
+    /// Seed the deals, where the n-th game of each table is dealt with `seed + n`.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.settings.seed = Some(seed);
+        self
+    }
+
+    /// Write event logs of the tables to `log_dir`.
+    pub fn log_dir(mut self, log_dir: impl Into<PathBuf>) -> Self {
+        self.settings.log_dir = Some(log_dir.into());
+        self
+    }
+
+    /// Update ratings in file at `path` after each game, see [`Ratings`].
+    pub fn ratings(mut self, path: impl Into<PathBuf>) -> Self {
+        self.ratings = Some(path.into());
+        self
+    }
+
+    /// Also accept browsers from `listener`, serving them the bundled browser client
+    /// which talks the same messages over a WebSocket.
+    pub fn web(mut self, listener: TcpListener) -> Self {
+        self.web = Some(listener);
+        self
+    }
+
+    /// Accept clients from `listener` until it fails.
+    pub fn serve(mut self, listener: TcpListener) -> io::Result<()> {
+        let settings = &self.settings;
+        validate(settings.n_players, &settings.rules, &settings.bot)
+            .map_err(|message| io::Error::new(io::ErrorKind::InvalidInput, message))?;
+        if let Some(log_dir) = &settings.log_dir {
+            fs::create_dir_all(log_dir)?;
+        }
+        if let Some(path) = &self.ratings {
+            self.settings.ratings = Some(Arc::new(RatingsFile::load(path)?));
+        }
+        log!("Serving on {}", listener.local_addr()?);
+        let lobby = Arc::new(Lobby::new(self.settings));
+        if let Some(web) = self.web {
+            log!("Serving browser client on http://{}", web.local_addr()?);
+            let lobby = lobby.clone();
+            thread::spawn(move || {
+                for stream in web.incoming().map_while(Result::ok) {
+                    let lobby = lobby.clone();
+                    thread::spawn(move || web::handle_connection(lobby, stream));
+                }
+            });
+        }
+        for stream in listener.incoming() {
+            let stream = stream?;
+            let lobby = lobby.clone();
+            thread::spawn(move || handle_connection(lobby, stream));
+        }
+        Ok(())
+    }
+}
+
+/// Exchange messages with the client connected by `stream` until it disconnects.
+fn handle_connection(lobby: Arc<Lobby>, stream: TcpStream) {
+    let Ok(mut writer) = stream.try_clone() else {
+        return;
+    };
+    let (outbox, messages) = mpsc::channel::<ServerMessage>();
+    thread::spawn(move || {
+        for message in messages {
+            let line = serde_json::to_string(&message).expect("messages are serializable");
+            if writeln!(writer, "{}", line).is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut session = Session::new(lobby, outbox);
+    for line in BufReader::new(stream).lines().map_while(Result::ok) {
+        session.receive(&line);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*; // bring private functions into scope
+    use serde_json::{json, Value};
+    use std::net::SocketAddr;
+
+    // helper client talking to the server over a local socket
+    struct Client {
+        reader: BufReader<TcpStream>,
+        writer: TcpStream,
+    }
+
+    impl Client {
+        fn connect(addr: SocketAddr) -> Self {
+            let writer = TcpStream::connect(addr).unwrap();
+            writer
+                .set_read_timeout(Some(Duration::from_secs(10)))
+                .unwrap();
+            let reader = BufReader::new(writer.try_clone().unwrap());
+            Self { reader, writer }
+        }
+
+        fn send(&mut self, message: Value) {
+            writeln!(self.writer, "{}", message).unwrap();
+        }
+
+        fn receive(&mut self) -> Value {
+            let mut line = String::new();
+            self.reader.read_line(&mut line).unwrap();
+            serde_json::from_str(&line).unwrap()
+        }
+
+        // receive messages until one of type `kind`
+        fn receive_type(&mut self, kind: &str) -> Value {
+            loop {
+                let message = self.receive();
+                if message["type"] == kind {
+                    return message;
+                }
+            }
+        }
+
+        // answer requests by playing the first legal card until the game is over
+        fn play_game(&mut self) -> Value {
+            loop {
+                let message = self.receive();
+                match message["type"].as_str().unwrap() {
+                    "turn" => {
+                        let view = &message["view"];
+                        let player = view["player"].as_u64().unwrap() as usize;
+                        assert_eq!(
+                            view["hand"].as_array().unwrap().len(),
+                            view["hand_sizes"][player]
+                        );
+                        let card = &message["legal"][0];
+                        self.send(json!({"type": "play", "id": message["id"], "card": card}));
+                    }
+                    "choose_color" => {
+                        self.send(json!({"type": "color", "id": message["id"], "color": "Red"}))
+                    }
+                    "game_over" => return message,
+                    _ => (),
+                }
+            }
+        }
+    }
+
+    fn serve(server: Server) -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            set_verbose(false);
+            server.serve(listener)
+        });
+        addr
+    }
+
+    fn start_server(n_players: usize, timeout: Duration) -> SocketAddr {
+        serve(
+            Server::new(n_players, Rules::official())
+                .timeout(timeout)
+                .seed(42),
+        )
+    }
+
+    #[test]
+    fn test_server_plays_game_with_bot() {
+        let addr = start_server(2, TIMEOUT);
+        let mut client = Client::connect(addr);
+        client.send(json!({"type": "join", "name": "Ann"}));
+        let seated = client.receive_type("seated");
+        assert_eq!(seated["seat"], 0);
+        assert_eq!(seated["n_players"], 2);
+        let players = client.receive_type("players");
+        assert_eq!(players["names"], json!(["Ann", null]));
+
+        client.send(json!({"type": "start"}));
+        assert_eq!(client.receive_type("started")["game"], 0);
+        let result = client.play_game();
+        assert!(result["winner"].as_u64().unwrap() < 2);
+
+        // next game can be started after the game is over
+        client.send(json!({"type": "start"}));
+        assert_eq!(client.receive_type("started")["game"], 1);
+    }
+
+    #[test]
+    fn test_server_sends_only_own_view() {
+        let addr = start_server(2, TIMEOUT);
+        let mut clients = [Client::connect(addr), Client::connect(addr)];
+        for (index, client) in clients.iter_mut().enumerate() {
+            client.send(json!({"type": "join", "name": index.to_string()}));
+            assert_eq!(client.receive_type("seated")["seat"], index);
+        }
+        clients[0].send(json!({"type": "start"}));
+        for (index, client) in clients.iter_mut().enumerate() {
+            let view = client.receive_type("view");
+            assert_eq!(view["view"]["player"], index);
+        }
+    }
+
+    #[test]
+    fn test_server_times_out() {
+        let addr = start_server(2, Duration::from_millis(100));
+        let mut client = Client::connect(addr);
+        client.send(json!({"type": "join", "name": "Ann"}));
+        client.send(json!({"type": "start"}));
+        let turn = client.receive_type("turn");
+        let timeout = client.receive_type("timeout");
+        assert_eq!(timeout["id"], turn["id"]);
+
+        // late answer is ignored and the game continues
+        client.send(json!({"type": "draw", "id": turn["id"]}));
+        client.play_game();
+    }
+
+    #[test]
+    fn test_server_rejoin() {
+        let addr = start_server(2, TIMEOUT);
+        let mut client = Client::connect(addr);
+        client.send(json!({"type": "join", "name": "Ann"}));
+        let token = client.receive_type("seated")["token"].clone();
+        drop(client);
+
+        // seat is kept for the disconnected player
+        let mut other = Client::connect(addr);
+        other.send(json!({"type": "join", "name": "Bob"}));
+        assert_eq!(other.receive_type("seated")["seat"], 1);
+        drop(other);
+
+        let mut client = Client::connect(addr);
+        client.send(json!({"type": "rejoin", "token": token}));
+        let seated = client.receive_type("seated");
+        assert_eq!(seated["seat"], 0);
+        assert_eq!(seated["token"], token);
+        client.send(json!({"type": "start"}));
+        client.play_game();
+    }
+
+    #[rstest::rstest]
+    #[case::invalid_message(json!({"type": "fold"}), "invalid message")]
+    #[case::not_seated(json!({"type": "start"}), "not seated")]
+    #[case::unknown_token(json!({"type": "rejoin", "token": "0"}), "unknown token")]
+    #[case::unknown_table(json!({"type": "spectate", "table": 9}), "unknown table")]
+    #[case::unknown_preset(json!({"type": "create_table", "rules": "uno"}), "unknown rules preset")]
+    #[case::unsupported_players(json!({"type": "create_table", "n_players": 1}), "unsupported")]
+    #[case::too_few_cards(json!({"type": "create_table", "n_players": 2, "rules": {"n_initial_cards": 60}}), "invalid rules")]
+    #[case::too_many_players(json!({"type": "create_table", "n_players": 10, "rules": {"n_initial_cards": 15}}), "invalid rules")]
+    #[case::not_at_table(json!({"type": "leave"}), "not at a table")]
+    fn test_server_error(#[case] message: Value, #[case] error: &str) {
+        let addr = start_server(2, TIMEOUT);
+        let mut client = Client::connect(addr);
+        client.send(message);
+        let answer = client.receive_type("error");
+        assert!(answer["message"].as_str().unwrap().starts_with(error));
+    }
+
+    #[test]
+    fn test_server_table_is_full() {
+        let addr = start_server(2, TIMEOUT);
+        let mut clients = [Client::connect(addr), Client::connect(addr)];
+        for (client, name) in clients.iter_mut().zip(["Ann", "Bob"]) {
+            client.send(json!({"type": "join", "name": name}));
+            client.receive_type("seated");
+        }
+        let mut client = Client::connect(addr);
+        client.send(json!({"type": "join", "name": "Cid"}));
+        assert_eq!(client.receive_type("error")["message"], "table is full");
+    }
+
+    #[test]
+    fn test_server_name_is_taken() {
+        let addr = start_server(3, TIMEOUT);
+        let mut client = Client::connect(addr);
+        client.send(json!({"type": "join", "name": "Ann"}));
+        client.receive_type("seated");
+        let mut other = Client::connect(addr);
+        other.send(json!({"type": "join", "name": "Ann"}));
+        assert_eq!(other.receive_type("error")["message"], "name Ann is taken");
+    }
+
+    #[test]
+    fn test_server_illegal_play() {
+        let addr = start_server(2, TIMEOUT);
+        let mut client = Client::connect(addr);
+        client.send(json!({"type": "join", "name": "Ann"}));
+        client.send(json!({"type": "start"}));
+        let turn = client.receive_type("turn");
+        client.send(json!({"type": "play", "id": turn["id"], "card": "Flip:Pink"}));
+        assert_eq!(client.receive_type("error")["message"], "illegal play");
+        client.play_game();
+    }
+
+    #[test]
+    fn test_server_rejects_players() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let error = Server::new(1, Rules::official())
+            .serve(listener)
+            .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn test_server_lobby() {
+        let addr = start_server(2, TIMEOUT);
+        let mut client = Client::connect(addr);
+        let rules = json!({"n_initial_cards": 5});
+        client.send(json!({"type": "create_table", "n_players": 3, "rules": rules}));
+        assert_eq!(client.receive_type("table_created")["table"], 0);
+        client.send(json!({"type": "create_table", "rules": "no-mercy"}));
+        assert_eq!(client.receive_type("table_created")["table"], 1);
+
+        client.send(json!({"type": "join", "name": "Ann", "table": 1}));
+        let seated = client.receive_type("seated");
+        assert_eq!(seated["table"], 1);
+        assert_eq!(seated["n_players"], 2);
+        assert_eq!(seated["rules"]["mercy_limit"], 25);
+
+        client.send(json!({"type": "list_tables"}));
+        let tables = client.receive_type("tables")["tables"].clone();
+        assert_eq!(tables[0]["n_players"], 3);
+        assert_eq!(tables[0]["rules"]["n_initial_cards"], 5);
+        assert_eq!(tables[0]["players"], json!([null, null, null]));
+        assert_eq!(tables[1]["players"], json!(["Ann", null]));
+
+        client.send(json!({"type": "join", "name": "Ann", "table": 0}));
+        assert_eq!(
+            client.receive_type("error")["message"],
+            "already at a table"
+        );
+    }
+
+    #[test]
+    fn test_server_spectator_sees_no_hand() {
+        let addr = start_server(2, TIMEOUT);
+        let mut player = Client::connect(addr);
+        player.send(json!({"type": "join", "name": "Ann"}));
+        player.receive_type("seated");
+
+        let mut spectator = Client::connect(addr);
+        spectator.send(json!({"type": "spectate", "table": 0}));
+        assert_eq!(spectator.receive_type("spectating")["table"], 0);
+        spectator.send(json!({"type": "start"}));
+        assert_eq!(spectator.receive_type("error")["message"], "not seated");
+
+        player.send(json!({"type": "start"}));
+        let view = spectator.receive_type("view")["view"].clone();
+        assert_eq!(view["player"], Value::Null);
+        assert_eq!(view["hand"], json!([]));
+        assert_eq!(view["hand_sizes"], json!([7, 7]));
+    }
+
+    #[test]
+    fn test_server_closes_empty_table() {
+        let addr = start_server(2, TIMEOUT);
+        let mut client = Client::connect(addr);
+        client.send(json!({"type": "join", "name": "Ann"}));
+        client.receive_type("seated");
+        client.send(json!({"type": "leave"}));
+        assert_eq!(client.receive_type("left")["table"], 0);
+
+        client.send(json!({"type": "list_tables"}));
+        assert_eq!(client.receive_type("tables")["tables"], json!([]));
+
+        // joining without table opens a new one
+        client.send(json!({"type": "join", "name": "Ann"}));
+        assert_eq!(client.receive_type("seated")["table"], 1);
+    }
+
+    #[test]
+    fn test_server_expires_abandoned_table() {
+        let server = Server::new(2, Rules::official()).expiry(Duration::from_millis(100));
+        let addr = serve(server);
+        let mut client = Client::connect(addr);
+        client.send(json!({"type": "join", "name": "Ann"}));
+        client.receive_type("seated");
+        let mut other = Client::connect(addr);
+        other.send(json!({"type": "spectate", "table": 0}));
+        other.receive_type("spectating");
+        drop(client);
+        drop(other);
+
+        // disconnected seats are freed once nobody has come back in time
+        let mut client = Client::connect(addr);
+        let deadline = Instant::now() + TIMEOUT;
+        loop {
+            client.send(json!({"type": "list_tables"}));
+            if client.receive_type("tables")["tables"] == json!([]) {
+                break;
+            }
+            assert!(Instant::now() < deadline, "table is not closed");
+            thread::sleep(Duration::from_millis(50));
+        }
+    }
+
+    #[test]
+    fn test_server_writes_event_log() {
+        let dir = std::env::temp_dir().join(format!("uno-log-{}", rand::random::<u64>()));
+        let addr = serve(Server::new(2, Rules::official()).seed(42).log_dir(&dir));
+        let mut client = Client::connect(addr);
+        client.send(json!({"type": "join", "name": "Ann"}));
+        client.send(json!({"type": "start"}));
+        let result = client.play_game();
+
+        let log = fs::read_to_string(dir.join("table-0.jsonl")).unwrap();
+        let events: Vec<Value> = log
+            .lines()
+            .map(|x| serde_json::from_str(x).unwrap())
+            .collect();
+        assert_eq!(events[0]["event"], "table_opened");
+        assert_eq!(events[1]["event"], "joined");
+        assert_eq!(events[1]["name"], "Ann");
+        assert_eq!(events[2]["event"], "game_started");
+        assert!(events.iter().all(|x| x["table"] == 0 && x["time"].is_u64()));
+        assert!(events.iter().any(|x| x["event"] == "turn"));
+        let last = events.last().unwrap();
+        assert_eq!(last["event"], "game_over");
+        assert_eq!(last["winner"], result["winner"]);
+        assert_eq!(last["stats"], result["stats"]);
+        assert_eq!(result["stats"]["players"].as_array().unwrap().len(), 2);
+        assert!(events.iter().any(|x| x["event"] == "card_played"));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_server_updates_ratings() {
+        let path = std::env::temp_dir().join(format!("uno-ratings-{}.json", rand::random::<u64>()));
+        let server = Server::new(3, Rules::official()).timeout(TIMEOUT);
+        let addr = serve(server.seed(42).ratings(&path));
+        let mut other = Client::connect(addr);
+        other.send(json!({"type": "join", "name": "Bob"}));
+        other.receive_type("seated");
+        drop(other);
+        let mut client = Client::connect(addr);
+        client.send(json!({"type": "join", "name": "Ann"}));
+        client.send(json!({"type": "start"}));
+        let result = client.play_game();
+
+        // the bot in the empty seat is not rated
+        let ratings = Ratings::load(&path).unwrap();
+        let (ann, bob, bot) = (ratings.get("Ann"), ratings.get("Bob"), ratings.get(BOT));
+        assert_eq!((ann.n_games, bob.n_games, bot.n_games), (1, 1, 0));
+        let placements = result["placements"].as_array().unwrap();
+        let is_ann_ahead =
+            placements.iter().position(|x| x == 1) < placements.iter().position(|x| x == 0);
+        assert_eq!(ann.rating > bob.rating, is_ann_ahead);
+        fs::remove_file(path).unwrap();
+    }
+}
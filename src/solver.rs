@@ -0,0 +1,721 @@
+use crate::greedy::most_held_color;
+use crate::{
+    filter_legal_cards, remove_duplicates, Card, Cards, Color, Direction, DrawMode, Play,
+    PlayerView, Rules, SaveWildsStrategy, Side, Stacking, Strategy, Symbol,
+};
+use rand::seq::SliceRandom;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+const ENDGAME_CARDS: usize = 6; // default number of cards in all hands from which to solve
+const MAX_CARDS: usize = 10; // default number of cards in the deck and hands searched exactly
+const N_SAMPLES: usize = 8; // default number of sampled deals of the unknown cards
+
+/// Phase of the turn of the current player.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Phase {
+    /// Play a card from hand or draw.
+    Turn,
+    /// Draw a card, after not playing from hand or keeping a card drawn before.
+    Drawing,
+    /// Play the legal card just drawn or keep it.
+    Drawn(Card),
+    /// Draw the given number of penalty cards and lose the turn.
+    Penalty(usize),
+}
+
+/// Perfect-information state of a game, where all hands are visible and only the order
+/// of the deck is unknown.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct State {
+    hands: Vec<Cards>, // sorted, so that states holding the same cards are equal
+    deck: Cards,       // sorted, as the order is unknown
+    top_card: Card,
+    current: usize,
+    is_reversed: bool,
+    phase: Phase,
+    n_passes: usize, // players in a row ending their turn without playing or drawing
+}
+
+impl State {
+    /// Get player `n_steps` players after player at `index` in the direction of play,
+    /// skipping players without cards, who have been eliminated.
+    fn next_player(&self, index: usize, n_steps: usize) -> usize {
+        let n_players = self.hands.len();
+        let mut next = index;
+        for _ in 0..n_steps {
+            loop {
+                next = match self.is_reversed {
+                    true => (next + n_players - 1) % n_players,
+                    false => (next + 1) % n_players,
+                };
+                if !self.hands[next].is_empty() {
+                    break;
+                }
+            }
+        }
+        next
+    }
+
+    /// Get number of players with cards.
+    fn n_active(&self) -> usize {
+        self.hands.iter().filter(|x| !x.is_empty()).count()
+    }
+
+    /// Continue with the turn of the player `n_steps` after the current player.
+    fn advance(mut self, n_steps: usize) -> Self {
+        self.current = self.next_player(self.current, n_steps);
+        self.phase = Phase::Turn;
+        self
+    }
+
+    /// End the turn of the current player, who can neither play nor draw.
+    fn pass(self) -> Self {
+        let n_passes = self.n_passes + 1;
+        Self {
+            n_passes,
+            ..self.advance(1)
+        }
+    }
+
+    /// Check if every player passed in a row, so that the game cannot go on without
+    /// shuffling the pile into the deck.
+    fn is_blocked(&self) -> bool {
+        self.n_passes >= self.n_active()
+    }
+
+    /// Get number of cards in the deck and all hands.
+    fn n_cards(&self) -> usize {
+        self.deck.len() + self.hands.iter().map(|x| x.len()).sum::<usize>()
+    }
+
+    /// Move one copy of `card` from the deck into the hand of the current player.
+    fn draw(mut self, card: &Card) -> Self {
+        let position = self.deck.iter().position(|x| x == card);
+        self.deck.remove(position.expect("card not in deck"));
+        self.n_passes = 0;
+        let hand = &mut self.hands[self.current];
+        hand.push(*card);
+        sort_cards(hand);
+        self
+    }
+
+    /// Play `card` from the hand of the current player and execute its action, returning
+    /// the next state or `None` if the player has won.
+    fn play(mut self, card: Card) -> Option<Self> {
+        let hand = &mut self.hands[self.current];
+        let position = hand
+            .iter()
+            .position(|x| x.is_equal_ignore_wild_color(&card));
+        hand.remove(position.expect("card not in hand"));
+        if hand.is_empty() {
+            return None;
+        }
+        self.top_card = card;
+        self.n_passes = 0;
+
+        let state = match card.symbol {
+            Symbol::Skip => self.advance(2),
+            Symbol::SkipEveryone => self.advance(0),
+            // with two players, reverse acts like skip
+            Symbol::Reverse if self.n_active() == 2 => self.advance(2),
+            Symbol::Reverse => {
+                self.is_reversed = !self.is_reversed;
+                self.advance(1)
+            }
+            _ if card.draw_amount() > 0 => {
+                if card.symbol == Symbol::WildReverseDraw4 && self.n_active() > 2 {
+                    self.is_reversed = !self.is_reversed;
+                }
+                let mut state = self.advance(1);
+                state.phase = Phase::Penalty(card.draw_amount());
+                state
+            }
+            _ => self.advance(1),
+        };
+        Some(state)
+    }
+
+    /// Estimate win probabilities beyond the search depth, proportional to the inverse
+    /// of the number of cards in hand.
+    fn estimate(&self) -> Vec<f64> {
+        let weights: Vec<f64> = self
+            .hands
+            .iter()
+            .map(|x| match x.len() {
+                0 => 0.0,
+                n => 1.0 / n as f64,
+            })
+            .collect();
+        let total: f64 = weights.iter().sum();
+        weights.into_iter().map(|x| x / total).collect()
+    }
+}
+
+/// Sort `cards` into a canonical order.
+fn sort_cards(cards: &mut Cards) {
+    cards.sort_by_cached_key(|card| {
+        let mut hasher = DefaultHasher::new();
+        card.hash(&mut hasher);
+        hasher.finish()
+    });
+}
+
+/// Check if the solver supports the action of `card`.
+fn is_supported(card: &Card) -> bool {
+    !matches!(
+        card.symbol,
+        Symbol::Flip | Symbol::WildDrawColor | Symbol::DiscardAll | Symbol::WildColorRoulette
+    )
+}
+
+/// Get legal plays from `cards` onto `top_card`, with wild cards played in each color.
+fn colored_plays(cards: Cards, top_card: Card, rules: &Rules) -> Cards {
+    if cards.is_empty() {
+        return vec![];
+    }
+    let legal_cards = filter_legal_cards(cards, top_card, rules.wild_draw_4);
+    remove_duplicates(legal_cards)
+        .into_iter()
+        .flat_map(|card| match card.is_wild() {
+            true => card
+                .side
+                .colors()
+                .map(|color| Card {
+                    color: Some(color),
+                    ..card
+                })
+                .to_vec(),
+            false => vec![card],
+        })
+        .collect()
+}
+
+/// Expectimax solver computing the exact win probability of each legal play with all
+/// hands visible, by searching the game tree to the end of the game with chance nodes
+/// for the cards drawn from the deck. Every player maximizes the win probability of
+/// their team, nobody challenges wild draw 4s or forgets to call "Uno", and the pile is
+/// not shuffled back into the deck, so that nobody wins once every player in a row can
+/// neither play nor draw. Only positions with at most [`Solver::max_cards`] cards in the
+/// deck and all hands are solved, unless the search is limited by [`Solver::max_depth`].
+#[derive(Debug, Clone)]
+pub struct Solver {
+    rules: Rules,
+    max_cards: usize,
+    max_depth: Option<usize>,
+    cache: HashMap<(State, usize), Vec<f64>>,
+}
+
+impl Solver {
+    /// Create solver for games played with `rules`.
+    pub fn new(rules: Rules) -> Self {
+        Self {
+            rules,
+            max_cards: MAX_CARDS,
+            max_depth: None,
+            cache: HashMap::new(),
+        }
+    }
+
+    /// Solve positions with at most `max_cards` cards in the deck and all hands.
+    pub fn max_cards(mut self, max_cards: usize) -> Self {
+        self.max_cards = max_cards;
+        self
+    }
+
+    /// Search at most `max_depth` plies, including draws, and estimate the values of
+    /// positions there by the hand sizes, which also allows analyzing positions with
+    /// more than [`Solver::max_cards`] cards. Values are then only estimates.
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        assert!(max_depth > 0, "maximum depth must be positive");
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// Check if values are exact, i.e. the search is not limited by a maximum depth.
+    pub fn is_exact(&self) -> bool {
+        self.max_depth.is_none()
+    }
+
+    /// Check if `rules` are supported, i.e. without stacking, 7-0, jump-in, the card
+    /// launcher, the mercy limit and double-sided cards.
+    pub fn supports(rules: &Rules) -> bool {
+        rules.stacking == Stacking::Off
+            && !rules.seven_zero
+            && !rules.jump_in
+            && rules.launcher.is_none()
+            && rules.mercy_limit.is_none()
+            && !rules.deck.double_sided
+    }
+
+    /// Compute the win probability of each legal play of the player at `index`, given
+    /// all `hands`, the cards in the `deck` in any order, the `top_card` and the
+    /// `direction` of play, with wild cards played once in each color. Return `None` if
+    /// the rules or cards are not supported, or if the position holds too many cards for
+    /// an exact search.
+    pub fn analyze(
+        &mut self,
+        hands: Vec<Cards>,
+        deck: Cards,
+        top_card: Card,
+        index: usize,
+        direction: Direction,
+    ) -> Option<Vec<(Card, f64)>> {
+        let plays = colored_plays(hands[index].clone(), top_card, &self.rules);
+        self.analyze_plays(hands, deck, top_card, index, direction, plays)
+    }
+
+    /// Compute the win probability of each of `plays` from the hand of the player at
+    /// `index`, like [`Solver::analyze`].
+    fn analyze_plays(
+        &mut self,
+        mut hands: Vec<Cards>,
+        mut deck: Cards,
+        top_card: Card,
+        index: usize,
+        direction: Direction,
+        plays: Cards,
+    ) -> Option<Vec<(Card, f64)>> {
+        let is_supported = hands
+            .iter()
+            .flatten()
+            .chain(&deck)
+            .chain([&top_card])
+            .all(is_supported);
+        if !Self::supports(&self.rules) || !is_supported {
+            return None;
+        }
+        hands.iter_mut().for_each(sort_cards);
+        sort_cards(&mut deck);
+        let state = State {
+            hands,
+            deck,
+            top_card,
+            current: index,
+            is_reversed: direction == Direction::Reverse,
+            phase: Phase::Turn,
+            n_passes: 0,
+        };
+        if self.is_exact() && state.n_cards() > self.max_cards {
+            return None;
+        }
+
+        // without a maximum depth, the search ends before the depth runs out, as the
+        // game tree is finite
+        let depth = self.max_depth.unwrap_or(usize::MAX);
+        let values = plays
+            .into_iter()
+            .map(|card| {
+                let values = self.play_value(&state, card, depth);
+                (card, self.team_value(&values, index))
+            })
+            .collect();
+        Some(values)
+    }
+
+    /// Get sum of win probabilities `values` of the team of player at `index`.
+    fn team_value(&self, values: &[f64], index: usize) -> f64 {
+        if !self.rules.teams {
+            return values[index];
+        }
+        let n_teams = values.len() / 2;
+        let is_partner = |other: &usize| other % n_teams == index % n_teams;
+        (0..values.len())
+            .filter(is_partner)
+            .map(|x| values[x])
+            .sum()
+    }
+
+    /// Get win probabilities of all players in `state`, searching `depth` plies.
+    fn value(&mut self, state: &State, depth: usize) -> Vec<f64> {
+        if state.is_blocked() {
+            return vec![0.0; state.hands.len()];
+        }
+        if depth == 0 {
+            return state.estimate();
+        }
+
+        // values of exact searches do not depend on the remaining depth
+        let key = match self.is_exact() {
+            true => (state.clone(), 0),
+            false => (state.clone(), depth),
+        };
+        if let Some(values) = self.cache.get(&key) {
+            return values.clone();
+        }
+
+        let values = match state.phase {
+            // play a legal card from hand or draw instead
+            Phase::Turn => {
+                let hand = state.hands[state.current].clone();
+                let plays = colored_plays(hand, state.top_card, &self.rules);
+                let drawing = State {
+                    phase: Phase::Drawing,
+                    ..state.clone()
+                };
+                self.best_value(state, plays, drawing, depth)
+            }
+            Phase::Drawing if state.deck.is_empty() => self.value(&state.clone().pass(), depth - 1),
+            Phase::Drawing => {
+                let top_card = state.top_card;
+                let rules = self.rules.clone();
+                self.chance_value(state, depth, |mut state, card| {
+                    let is_legal = !colored_plays(vec![card], top_card, &rules).is_empty();
+                    state.phase = match (is_legal, rules.draw_mode) {
+                        (true, _) => Phase::Drawn(card),
+                        (false, DrawMode::UntilPlayable) => Phase::Drawing,
+                        (false, DrawMode::DrawOne) => return state.advance(1),
+                    };
+                    state
+                })
+            }
+            // play the drawn card or keep it, ending the turn unless drawing on
+            Phase::Drawn(card) => {
+                let plays = colored_plays(vec![card], state.top_card, &self.rules);
+                let keeping = match self.rules.draw_mode {
+                    DrawMode::DrawOne => state.clone().advance(1),
+                    DrawMode::UntilPlayable => State {
+                        phase: Phase::Drawing,
+                        ..state.clone()
+                    },
+                };
+                self.best_value(state, plays, keeping, depth)
+            }
+            Phase::Penalty(0) => self.value(&state.clone().advance(1), depth - 1),
+            Phase::Penalty(_) if state.deck.is_empty() => {
+                self.value(&state.clone().pass(), depth - 1)
+            }
+            // only the number of cards drawn matters when reaching the search depth
+            Phase::Penalty(n_cards) if depth <= n_cards && depth <= state.deck.len() => {
+                let mut state = state.clone();
+                let cards: Cards = state.deck.drain(..depth).collect();
+                state.hands[state.current].extend(cards);
+                state.estimate()
+            }
+            Phase::Penalty(n_cards) => self.chance_value(state, depth, |mut state, _| {
+                state.phase = Phase::Penalty(n_cards - 1);
+                state
+            }),
+        };
+        self.cache.insert(key, values.clone());
+        values
+    }
+
+    /// Get values after the best of `plays` for the team of the current player, or after
+    /// the `other` state if it is better.
+    fn best_value(&mut self, state: &State, plays: Cards, other: State, depth: usize) -> Vec<f64> {
+        let mut best = self.value(&other, depth - 1);
+        for card in plays {
+            let values = self.play_value(state, card, depth);
+            if self.team_value(&values, state.current) > self.team_value(&best, state.current) {
+                best = values;
+            }
+        }
+        best
+    }
+
+    /// Get values after the current player plays `card`.
+    fn play_value(&mut self, state: &State, card: Card, depth: usize) -> Vec<f64> {
+        match state.clone().play(card) {
+            Some(next) => self.value(&next, depth - 1),
+            None => {
+                let mut values = vec![0.0; state.hands.len()];
+                values[state.current] = 1.0;
+                values
+            }
+        }
+    }
+
+    /// Get values averaged over the cards the current player can draw from the deck,
+    /// continuing with the state returned by `next` for each drawn card.
+    fn chance_value(
+        &mut self,
+        state: &State,
+        depth: usize,
+        next: impl Fn(State, Card) -> State,
+    ) -> Vec<f64> {
+        // only the number of cards drawn matters when reaching the search depth
+        if depth == 1 {
+            let card = state.deck[0];
+            return next(state.clone().draw(&card), card).estimate();
+        }
+
+        let n_cards = state.deck.len() as f64;
+        let mut values = vec![0.0; state.hands.len()];
+        for card in remove_duplicates(state.deck.clone()) {
+            let count = state.deck.iter().filter(|&x| *x == card).count();
+            let state = next(state.clone().draw(&card), card);
+            let probability = count as f64 / n_cards;
+            for (value, x) in values.iter_mut().zip(self.value(&state, depth - 1)) {
+                *value += probability * x;
+            }
+        }
+        values
+    }
+}
+
+/// Strategy solving the endgame with the [`Solver`], averaging the win probabilities of
+/// plays over sampled deals of the unknown cards, and saving wild cards before the
+/// endgame or if the rules or the position are not supported.
+#[derive(Debug, Clone)]
+pub struct SolverStrategy {
+    rules: Rules,
+    endgame_cards: usize,
+    n_samples: usize,
+    max_cards: usize,
+    max_depth: Option<usize>,
+}
+
+impl SolverStrategy {
+    /// Create strategy for games played with `rules`.
+    pub fn new(rules: Rules) -> Self {
+        Self {
+            rules,
+            endgame_cards: ENDGAME_CARDS,
+            n_samples: N_SAMPLES,
+            max_cards: MAX_CARDS,
+            max_depth: None,
+        }
+    }
+
+    /// Start solving once all hands hold at most `endgame_cards` cards in total.
+    pub fn endgame_cards(mut self, endgame_cards: usize) -> Self {
+        self.endgame_cards = endgame_cards;
+        self
+    }
+
+    /// Solve `n_samples` deals of the unknown cards per decision.
+    pub fn n_samples(mut self, n_samples: usize) -> Self {
+        assert!(n_samples > 0, "number of samples must be positive");
+        self.n_samples = n_samples;
+        self
+    }
+
+    /// Solve deals with at most `max_cards` cards, see [`Solver::max_cards`].
+    pub fn max_cards(mut self, max_cards: usize) -> Self {
+        self.max_cards = max_cards;
+        self
+    }
+
+    /// Search at most `max_depth` plies per deal, see [`Solver::max_depth`].
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        assert!(max_depth > 0, "maximum depth must be positive");
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// Select play from `legal_cards` with the highest average win probability over
+    /// deals consistent with `view`, or `None` if the rules or cards are not supported.
+    fn solve(&self, legal_cards: Cards, view: &PlayerView) -> Option<Card> {
+        // legal cards just drawn are not in hand yet, but among the unknown cards
+        let mut hand = view.hand.clone();
+        let mut unknown_cards = view.unknown_cards.clone();
+        for card in &legal_cards {
+            if !hand.iter().any(|x| x.is_equal_ignore_wild_color(card)) {
+                let position = unknown_cards
+                    .iter()
+                    .position(|x| x.is_equal_ignore_wild_color(card))?;
+                hand.push(unknown_cards.remove(position));
+            }
+        }
+
+        let plays = colored_plays(legal_cards, view.top_card, &self.rules);
+        let mut totals = vec![0.0; plays.len()];
+        let mut solver = Solver::new(self.rules.clone()).max_cards(self.max_cards);
+        if let Some(max_depth) = self.max_depth {
+            solver = solver.max_depth(max_depth);
+        }
+        for _ in 0..self.n_samples {
+            unknown_cards.shuffle(&mut rand::thread_rng());
+            let mut cards = unknown_cards.iter().copied();
+            let hands: Vec<Cards> = view
+                .hand_sizes
+                .iter()
+                .enumerate()
+                .map(|(other, &n_cards)| match other == view.index {
+                    true => hand.clone(),
+                    false => cards.by_ref().take(n_cards).collect(),
+                })
+                .collect();
+            let deck = cards.collect();
+            let index = view.index;
+            let values = solver.analyze_plays(
+                hands,
+                deck,
+                view.top_card,
+                index,
+                view.direction,
+                plays.clone(),
+            )?;
+            for (total, (_, value)) in totals.iter_mut().zip(values) {
+                *total += value;
+            }
+        }
+
+        // prefer earlier plays on ties
+        let best = (0..plays.len())
+            .rev()
+            .max_by(|&a, &b| totals[a].total_cmp(&totals[b]))?;
+        Some(plays[best])
+    }
+}
+
+impl Strategy for SolverStrategy {
+    /// Select card without further information, saving wild cards.
+    fn select_card(&self, legal_cards: Cards) -> Play {
+        SaveWildsStrategy {}.select_card(legal_cards)
+    }
+
+    fn select_card_with_view(&self, legal_cards: Cards, view: &PlayerView) -> Play {
+        let n_cards: usize = view.hand_sizes.iter().sum();
+        if n_cards <= self.endgame_cards {
+            if let Some(card) = self.solve(legal_cards.clone(), view) {
+                return Some(card);
+            }
+        }
+        SaveWildsStrategy {}.select_card_with_view(legal_cards, view)
+    }
+
+    fn select_color(&self, hand: &Cards, side: Side) -> Color {
+        most_held_color(hand, side)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*; // bring private functions into scope
+    use rstest::rstest;
+
+    fn card(symbol: Symbol, color: Color) -> Card {
+        Card::new(symbol, Some(color))
+    }
+
+    fn value_of(values: &[(Card, f64)], card: Card) -> f64 {
+        let value = values.iter().find(|(x, _)| *x == card);
+        value.expect("card not analyzed").1
+    }
+
+    #[test]
+    fn test_solver_skip_wins() {
+        let mut solver = Solver::new(Rules::official());
+        let skip = card(Symbol::Skip, Color::Red);
+        let one = card(Symbol::Number(1), Color::Red);
+        let hands = vec![vec![skip, one], vec![card(Symbol::Number(2), Color::Red)]];
+        let top_card = card(Symbol::Number(3), Color::Red);
+        let values = solver
+            .analyze(hands, vec![], top_card, 0, Direction::Forward)
+            .unwrap();
+        assert_eq!(values.len(), 2);
+        assert_eq!(value_of(&values, skip), 1.0);
+        assert_eq!(value_of(&values, one), 0.0);
+    }
+
+    #[test]
+    fn test_solver_averages_over_draws() {
+        // after the 1, the next player wins if they draw the blue 1, otherwise the
+        // draw 2 wins, while playing the draw 2 first wins for sure
+        let mut solver = Solver::new(Rules::official());
+        let draw_2 = card(Symbol::Draw2, Color::Red);
+        let one = card(Symbol::Number(1), Color::Red);
+        let hands = vec![
+            vec![draw_2, one],
+            vec![card(Symbol::Number(5), Color::Blue)],
+        ];
+        let deck = vec![
+            card(Symbol::Number(1), Color::Blue),
+            card(Symbol::Number(6), Color::Green),
+        ];
+        let top_card = card(Symbol::Number(3), Color::Red);
+        let values = solver
+            .analyze(hands, deck, top_card, 0, Direction::Forward)
+            .unwrap();
+        assert_eq!(value_of(&values, draw_2), 1.0);
+        assert_eq!(value_of(&values, one), 0.5);
+    }
+
+    #[test]
+    fn test_solver_wild_in_each_color() {
+        let mut solver = Solver::new(Rules::official());
+        let hands = vec![
+            vec![
+                Card::new(Symbol::Wild, None),
+                card(Symbol::Number(7), Color::Blue),
+            ],
+            vec![
+                card(Symbol::Number(5), Color::Green),
+                card(Symbol::Number(5), Color::Yellow),
+            ],
+        ];
+        let top_card = card(Symbol::Number(3), Color::Red);
+        let values = solver
+            .analyze(hands, vec![], top_card, 0, Direction::Forward)
+            .unwrap();
+        assert_eq!(values.len(), 4);
+        assert_eq!(value_of(&values, card(Symbol::Wild, Color::Blue)), 1.0);
+    }
+
+    #[test]
+    fn test_solver_blocked_game() {
+        // with an empty deck, nobody can play and nobody wins
+        let mut solver = Solver::new(Rules::official());
+        let hands = vec![
+            vec![card(Symbol::Number(1), Color::Blue)],
+            vec![card(Symbol::Number(2), Color::Green)],
+        ];
+        let top_card = card(Symbol::Number(3), Color::Red);
+        let values = solver
+            .analyze(hands.clone(), vec![], top_card, 0, Direction::Forward)
+            .unwrap();
+        assert!(values.is_empty());
+        let state = State {
+            hands,
+            deck: vec![],
+            top_card,
+            current: 0,
+            is_reversed: false,
+            phase: Phase::Turn,
+            n_passes: 0,
+        };
+        assert_eq!(solver.value(&state, usize::MAX), vec![0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_solver_max_cards() {
+        let hands = vec![
+            vec![card(Symbol::Number(1), Color::Red); 2],
+            vec![card(Symbol::Number(2), Color::Blue); 2],
+        ];
+        let deck = vec![card(Symbol::Number(5), Color::Green); MAX_CARDS];
+        let top_card = card(Symbol::Number(3), Color::Red);
+        let mut solver = Solver::new(Rules::official());
+        assert!(solver.is_exact());
+        let values = solver.analyze(hands.clone(), deck.clone(), top_card, 0, Direction::Forward);
+        assert_eq!(values, None);
+
+        // positions too large for an exact search can be estimated
+        let mut solver = Solver::new(Rules::official()).max_depth(2);
+        assert!(!solver.is_exact());
+        let values = solver.analyze(hands, deck, top_card, 0, Direction::Forward);
+        assert_eq!(values.map(|x| x.len()), Some(1));
+    }
+
+    #[rstest]
+    #[case::official(Rules::official(), true)]
+    #[case::house_classic(Rules::house_classic(), false)]
+    #[case::no_mercy(Rules::no_mercy(), false)]
+    #[case::flip(Rules::flip(), false)]
+    #[case::attack(Rules::attack(), false)]
+    #[case::partners(Rules::partners(), true)]
+    fn test_solver_supports(#[case] rules: Rules, #[case] expected: bool) {
+        assert_eq!(Solver::supports(&rules), expected);
+    }
+
+    #[test]
+    fn test_team_value() {
+        let solver = Solver::new(Rules::partners());
+        let values = [0.1, 0.2, 0.3, 0.4];
+        assert_eq!(solver.team_value(&values, 0), 0.1 + 0.3);
+        assert_eq!(solver.team_value(&values, 3), 0.2 + 0.4);
+    }
+}
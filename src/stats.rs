@@ -0,0 +1,286 @@
+use crate::{Event, PenaltyCause};
+use serde::Serialize;
+
+/// Statistics of a player in a game.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct PlayerStats {
+    /// Number of cards played.
+    pub n_played: usize,
+    /// Number of cards drawn, including penalties.
+    pub n_drawn: usize,
+    /// Number of times skipped.
+    pub n_skipped: usize,
+    /// Number of times hit by a draw card, e.g. Draw2 or WildDraw4.
+    pub n_hit: usize,
+    /// Number of cards played changing the color.
+    pub n_color_changes: usize,
+    /// Longest run of own turns playing a card without drawing.
+    pub longest_streak: usize,
+    /// Number of turns started holding a single card.
+    pub n_turns_at_uno: usize,
+}
+
+/// Statistics of a game, derived from its events.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct GameStats {
+    /// Statistics of each player.
+    pub players: Vec<PlayerStats>,
+    /// Number of times the pile was recycled into the deck.
+    pub n_recycles: usize,
+}
+
+/// Turn in progress, given by the player and whether they played and drew cards.
+struct Turn {
+    player: usize,
+    has_played: bool,
+    has_drawn: bool,
+}
+
+impl GameStats {
+    /// Compute statistics of `n_players` from `events` of a game, see
+    /// [`Game::events`](crate::Game::events).
+    pub fn from_events(n_players: usize, events: &[Event]) -> Self {
+        let mut stats = Self {
+            players: vec![PlayerStats::default(); n_players],
+            n_recycles: 0,
+        };
+        let mut streaks = vec![0; n_players];
+        let mut turn: Option<Turn> = None;
+        for event in events {
+            match *event {
+                Event::TurnStarted { player, n_cards } => {
+                    stats.end_turn(turn.take(), &mut streaks);
+                    turn = Some(Turn {
+                        player,
+                        has_played: false,
+                        has_drawn: false,
+                    });
+                    if n_cards == 1 {
+                        stats.players[player].n_turns_at_uno += 1;
+                    }
+                }
+                Event::CardPlayed { player, .. } => {
+                    stats.players[player].n_played += 1;
+                    match &mut turn {
+                        Some(turn) if turn.player == player => turn.has_played = true,
+                        // jumping in takes over the turn
+                        _ => {
+                            stats.end_turn(turn.take(), &mut streaks);
+                            turn = Some(Turn {
+                                player,
+                                has_played: true,
+                                has_drawn: false,
+                            });
+                        }
+                    }
+                }
+                Event::CardsDrawn { player, n_cards }
+                | Event::PenaltyDrawn {
+                    player, n_cards, ..
+                } => {
+                    stats.players[player].n_drawn += n_cards;
+                    if matches!(
+                        event,
+                        Event::PenaltyDrawn {
+                            cause: PenaltyCause::DrawCard,
+                            ..
+                        }
+                    ) {
+                        stats.players[player].n_hit += 1;
+                    }
+                    if let Some(turn) = turn.as_mut().filter(|x| x.player == player) {
+                        turn.has_drawn = true;
+                    }
+                }
+                Event::UnoCaught {
+                    player, n_cards, ..
+                } => stats.players[player].n_drawn += n_cards,
+                Event::Skipped { player } => stats.players[player].n_skipped += 1,
+                Event::ColorChanged { player, .. } => stats.players[player].n_color_changes += 1,
+                Event::DeckRecycled => stats.n_recycles += 1,
+                Event::UnoCalled { .. } => (),
+            }
+        }
+        stats.end_turn(turn, &mut streaks);
+        stats
+    }
+
+    /// Extend or break the streak of the player of `turn`.
+    fn end_turn(&mut self, turn: Option<Turn>, streaks: &mut [usize]) {
+        let Some(turn) = turn else {
+            return;
+        };
+        match turn.has_played && !turn.has_drawn {
+            true => {
+                streaks[turn.player] += 1;
+                let stats = &mut self.players[turn.player];
+                stats.longest_streak = stats.longest_streak.max(streaks[turn.player]);
+            }
+            false => streaks[turn.player] = 0,
+        }
+    }
+
+    /// Get index of the player hit hardest by draw cards, if any player was hit.
+    pub fn hardest_hit(&self) -> Option<usize> {
+        (0..self.players.len())
+            .filter(|&x| self.players[x].n_hit > 0)
+            .max_by_key(|&x| (self.players[x].n_hit, self.players[x].n_drawn))
+    }
+}
+
+/// Format `stats` of players with `names` as report.
+pub fn fmt_stats(stats: &GameStats, names: &[&str]) -> String {
+    let width = names.iter().map(|x| x.len()).max().unwrap_or(0).max(6);
+    let mut lines = vec![format!(
+        "{:<width$} {:>6} {:>6} {:>7} {:>4} {:>7} {:>6} {:>6}",
+        "Player", "Played", "Drawn", "Skipped", "Hit", "Colors", "Streak", "At Uno"
+    )];
+    for (name, player) in names.iter().zip(&stats.players) {
+        lines.push(format!(
+            "{:<width$} {:>6} {:>6} {:>7} {:>4} {:>7} {:>6} {:>6}",
+            name,
+            player.n_played,
+            player.n_drawn,
+            player.n_skipped,
+            player.n_hit,
+            player.n_color_changes,
+            player.longest_streak,
+            player.n_turns_at_uno
+        ));
+    }
+    lines.push(format!("Deck recycles: {}", stats.n_recycles));
+    if let Some(index) = stats.hardest_hit() {
+        let player = &stats.players[index];
+        lines.push(format!(
+            "Hardest hit: {} ({} draw cards, {} cards drawn)",
+            names[index], player.n_hit, player.n_drawn
+        ));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*; // bring private functions into scope
+    use crate::{Card, Color, Game, Rules, Symbol};
+
+    fn card() -> Card {
+        Card::new(Symbol::Number(7), Some(Color::Red))
+    }
+
+    #[test]
+    fn test_from_events() {
+        let events = [
+            Event::TurnStarted {
+                player: 0,
+                n_cards: 2,
+            },
+            Event::CardPlayed {
+                player: 0,
+                card: card(),
+            },
+            Event::ColorChanged {
+                player: 0,
+                color: Color::Red,
+            },
+            Event::Skipped { player: 1 },
+            Event::TurnStarted {
+                player: 0,
+                n_cards: 1,
+            },
+            Event::CardsDrawn {
+                player: 0,
+                n_cards: 1,
+            },
+            Event::DeckRecycled,
+            Event::TurnStarted {
+                player: 1,
+                n_cards: 7,
+            },
+            Event::CardPlayed {
+                player: 1,
+                card: card(),
+            },
+            // player 0 jumps in
+            Event::CardPlayed {
+                player: 0,
+                card: card(),
+            },
+            Event::TurnStarted {
+                player: 1,
+                n_cards: 6,
+            },
+            Event::PenaltyDrawn {
+                player: 1,
+                n_cards: 4,
+                cause: PenaltyCause::DrawCard,
+            },
+            // roulette draws are no hits
+            Event::PenaltyDrawn {
+                player: 1,
+                n_cards: 2,
+                cause: PenaltyCause::Roulette,
+            },
+            Event::UnoCaught {
+                player: 0,
+                by: 1,
+                n_cards: 2,
+            },
+        ];
+        let stats = GameStats::from_events(2, &events);
+        let expected = [
+            PlayerStats {
+                n_played: 2,
+                n_drawn: 3,
+                n_color_changes: 1,
+                longest_streak: 1,
+                n_turns_at_uno: 1,
+                ..PlayerStats::default()
+            },
+            PlayerStats {
+                n_played: 1,
+                n_drawn: 6,
+                n_skipped: 1,
+                n_hit: 1,
+                longest_streak: 1,
+                ..PlayerStats::default()
+            },
+        ];
+        assert_eq!(stats.players, expected);
+        assert_eq!(stats.n_recycles, 1);
+        assert_eq!(stats.hardest_hit(), Some(1));
+    }
+
+    #[test]
+    fn test_from_game_events() {
+        let mut game = Game::with_seed(4, 3, Rules::official(), 42);
+        let result = game.play();
+        let stats = GameStats::from_events(4, game.events());
+
+        // the winner played the cards of their hand, counting cards drawn and passed
+        let winner = &stats.players[result.winner];
+        assert!(winner.n_played >= 7);
+        let n_played: usize = stats.players.iter().map(|x| x.n_played).sum();
+        let n_turns = game
+            .events()
+            .iter()
+            .filter(|x| matches!(x, Event::TurnStarted { .. }))
+            .count();
+        assert!(n_played <= n_turns);
+        assert!(stats.players.iter().all(|x| x.longest_streak <= x.n_played));
+    }
+
+    #[test]
+    fn test_fmt_stats() {
+        let mut stats = GameStats::from_events(2, &[]);
+        stats.players[1].n_hit = 2;
+        stats.players[1].n_drawn = 6;
+        let report = fmt_stats(&stats, &["Ann", "Bob"]);
+        let lines: Vec<_> = report.lines().collect();
+        assert_eq!(lines.len(), 5);
+        assert!(lines[0].starts_with("Player"));
+        assert!(lines[2].starts_with("Bob"));
+        assert_eq!(lines[3], "Deck recycles: 0");
+        assert_eq!(lines[4], "Hardest hit: Bob (2 draw cards, 6 cards drawn)");
+    }
+}
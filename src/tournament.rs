@@ -0,0 +1,489 @@
+use crate::{create_strategy, set_verbose, Game, Ratings, Rules};
+use itertools::Itertools;
+use std::collections::HashSet;
+use std::thread;
+
+const N_PLAYERS: usize = 2; // default number of players per table
+const N_GAMES: usize = 10; // default number of games per seat order
+const Z: f64 = 1.96; // quantile of the normal distribution for 95% confidence intervals
+
+/// Pairing of strategies into tables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pairing {
+    /// Play every combination of strategies.
+    RoundRobin,
+    /// Play `rounds`, each seating strategies with similar win rates at the same table,
+    /// avoiding tables that have been played before where possible.
+    Swiss { rounds: usize },
+}
+
+/// Standing of a strategy in a tournament.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Standing {
+    /// Strategy spec, see [`create_strategy`].
+    pub name: String,
+    /// Number of games played.
+    pub n_games: usize,
+    /// Number of games won, including wins of partners in partnership mode.
+    pub n_wins: usize,
+    /// Points scored in total.
+    pub points: u64,
+}
+
+impl Standing {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            n_games: 0,
+            n_wins: 0,
+            points: 0,
+        }
+    }
+
+    /// Get share of games won.
+    pub fn win_rate(&self) -> f64 {
+        match self.n_games {
+            0 => 0.0,
+            n => self.n_wins as f64 / n as f64,
+        }
+    }
+
+    /// Get Wilson score interval of the win rate with 95% confidence.
+    pub fn confidence_interval(&self) -> (f64, f64) {
+        if self.n_games == 0 {
+            return (0.0, 1.0);
+        }
+        let n = self.n_games as f64;
+        let p = self.win_rate();
+        let denominator = 1.0 + Z * Z / n;
+        let center = (p + Z * Z / (2.0 * n)) / denominator;
+        let margin = Z * (p * (1.0 - p) / n + Z * Z / (4.0 * n * n)).sqrt() / denominator;
+        ((center - margin).max(0.0), (center + margin).min(1.0))
+    }
+
+    /// Get average points scored per game.
+    pub fn average_points(&self) -> f64 {
+        match self.n_games {
+            0 => 0.0,
+            n => self.points as f64 / n as f64,
+        }
+    }
+}
+
+/// Format `standings` as table.
+pub fn fmt_standings(standings: &[Standing]) -> String {
+    let width = standings
+        .iter()
+        .map(|x| x.name.len())
+        .max()
+        .unwrap_or(0)
+        .max(8);
+    let mut lines = vec![format!(
+        "{:<4} {:<width$} {:>6} {:>6} {:>8} {:>16} {:>10}",
+        "Rank", "Strategy", "Games", "Wins", "Win rate", "95% CI", "Avg points"
+    )];
+    for (rank, standing) in standings.iter().enumerate() {
+        let (low, high) = standing.confidence_interval();
+        lines.push(format!(
+            "{:<4} {:<width$} {:>6} {:>6} {:>7.1}% {:>16} {:>10.1}",
+            rank + 1,
+            standing.name,
+            standing.n_games,
+            standing.n_wins,
+            100.0 * standing.win_rate(),
+            format!("[{:.1}%, {:.1}%]", 100.0 * low, 100.0 * high),
+            standing.average_points(),
+        ));
+    }
+    lines.join("\n")
+}
+
+/// Outcome of a single game, giving the strategies seated at the table and the winning
+/// seats with their points.
+struct Outcome {
+    entries: Vec<usize>,
+    winners: Vec<usize>,
+    placements: Vec<usize>,
+    points: u32,
+}
+
+/// Tournament between strategies, where each table is played in every seat order over
+/// the same seeded games.
+pub struct Tournament {
+    specs: Vec<String>,
+    rules: Rules,
+    n_players: usize,
+    n_games: usize,
+    seed: u64,
+    pairing: Pairing,
+}
+
+impl Tournament {
+    /// Create round-robin tournament between strategies given by `specs`, see
+    /// [`create_strategy`], playing with `rules`.
+    pub fn new(specs: Vec<String>, rules: Rules) -> Self {
+        Self {
+            specs,
+            rules,
+            n_players: N_PLAYERS,
+            n_games: N_GAMES,
+            seed: rand::random(),
+            pairing: Pairing::RoundRobin,
+        }
+    }
+
+    /// Set number of players at each table.
+    pub fn n_players(mut self, n_players: usize) -> Self {
+        self.n_players = n_players;
+        self
+    }
+
+    /// Set number of games played in each seat order of a table.
+    pub fn n_games(mut self, n_games: usize) -> Self {
+        self.n_games = n_games;
+        self
+    }
+
+    /// Set seed of the first game, where the n-th game of each seat order is dealt
+    /// with `seed + n`. Only the deals are seeded, so strategies deciding randomly,
+    /// like `random` and `ismcts`, still vary between runs.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Set pairing of strategies into tables.
+    pub fn pairing(mut self, pairing: Pairing) -> Self {
+        self.pairing = pairing;
+        self
+    }
+
+    /// Play tournament and return standings sorted by win rate.
+    pub fn run(&self) -> Result<Vec<Standing>, String> {
+        self.run_rated(&mut Ratings::default())
+    }
+
+    /// Play tournament, updating `ratings` of strategies by their specs after each
+    /// game, and return standings sorted by win rate.
+    pub fn run_rated(&self, ratings: &mut Ratings) -> Result<Vec<Standing>, String> {
+        if self.n_players < 2 || self.n_players > self.specs.len() {
+            return Err(format!(
+                "cannot seat {} players with {} strategies",
+                self.n_players,
+                self.specs.len()
+            ));
+        }
+        self.rules
+            .validate_players(self.n_players)
+            .map_err(|error| error.to_string())?;
+        if let Some((_, spec)) = self
+            .specs
+            .iter()
+            .enumerate()
+            .find(|(i, x)| self.specs[..*i].contains(x))
+        {
+            return Err(format!("duplicate strategy: {}", spec));
+        }
+        if let Some(spec) = self
+            .specs
+            .iter()
+            .find(|x| create_strategy(x, &self.rules).is_none())
+        {
+            return Err(format!("unknown strategy: {}", spec));
+        }
+
+        let mut standings: Vec<Standing> = self.specs.iter().map(|x| Standing::new(x)).collect();
+        match self.pairing {
+            Pairing::RoundRobin => {
+                let tables = (0..self.specs.len())
+                    .combinations(self.n_players)
+                    .collect_vec();
+                self.play_round(&tables, 0, &mut standings, ratings);
+            }
+            Pairing::Swiss { rounds } => {
+                let mut played = HashSet::new();
+                for round in 0..rounds {
+                    let tables = swiss_tables(&standings, self.n_players, &played);
+                    self.play_round(&tables, round, &mut standings, ratings);
+                    played.extend(tables);
+                }
+            }
+        }
+        standings.sort_by(|a, b| b.win_rate().total_cmp(&a.win_rate()));
+        Ok(standings)
+    }
+
+    /// Play `tables` of strategy indexes in every seat order in `round` and add the
+    /// outcomes to `standings` and `ratings`.
+    fn play_round(
+        &self,
+        tables: &[Vec<usize>],
+        round: usize,
+        standings: &mut [Standing],
+        ratings: &mut Ratings,
+    ) {
+        let seed = self.seed.wrapping_add((round * self.n_games) as u64);
+        let games = tables
+            .iter()
+            .flat_map(|table| table.iter().copied().permutations(table.len()))
+            .flat_map(|entries| {
+                (0..self.n_games).map(move |i| (entries.clone(), seed.wrapping_add(i as u64)))
+            })
+            .collect_vec();
+
+        // play games in parallel, creating games and strategies on each thread
+        let n_threads = thread::available_parallelism().map_or(1, |x| x.get());
+        let chunk_size = games.len().div_ceil(n_threads).max(1);
+        let outcomes: Vec<Outcome> = thread::scope(|scope| {
+            let handles = games
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    scope.spawn(move || {
+                        set_verbose(false);
+                        chunk
+                            .iter()
+                            .map(|(entries, seed)| self.play_game(entries, *seed))
+                            .collect_vec()
+                    })
+                })
+                .collect_vec();
+            handles
+                .into_iter()
+                .flat_map(|x| x.join().expect("game panicked"))
+                .collect()
+        });
+
+        for outcome in outcomes {
+            for &entry in &outcome.entries {
+                standings[entry].n_games += 1;
+            }
+            for &seat in &outcome.winners {
+                let standing = &mut standings[outcome.entries[seat]];
+                standing.n_wins += 1;
+                standing.points += outcome.points as u64;
+            }
+            let names = outcome
+                .entries
+                .iter()
+                .map(|&x| self.specs[x].clone())
+                .collect_vec();
+            ratings.update(&names, &outcome.placements);
+        }
+    }
+
+    /// Play game with `seed` between strategies at `entries`, seated in this order.
+    fn play_game(&self, entries: &[usize], seed: u64) -> Outcome {
+        let n_players = entries.len();
+        let mut game = Game::with_seed(n_players, n_players - 1, self.rules.clone(), seed);
+        for (index, &entry) in entries.iter().enumerate() {
+            let strategy =
+                create_strategy(&self.specs[entry], &self.rules).expect("known strategy");
+            game.set_strategy(index, strategy);
+        }
+        let result = game.play();
+        Outcome {
+            entries: entries.to_vec(),
+            winners: game.players.team_members(result.winner),
+            placements: result.placements,
+            points: result.points,
+        }
+    }
+}
+
+/// Seat strategies with similar win rates in `standings` at tables of `n_players`,
+/// avoiding tables that have been `played` where possible. Strategies left over sit out
+/// the round, starting with the lowest ranked of those who played the most games.
+fn swiss_tables(
+    standings: &[Standing],
+    n_players: usize,
+    played: &HashSet<Vec<usize>>,
+) -> Vec<Vec<usize>> {
+    let mut byes = (0..standings.len()).collect_vec();
+    byes.sort_by(|&a, &b| {
+        (standings[b].n_games.cmp(&standings[a].n_games))
+            .then(standings[a].win_rate().total_cmp(&standings[b].win_rate()))
+            .then(b.cmp(&a))
+    });
+    byes.truncate(standings.len() % n_players);
+
+    // rank by win rate, keeping the given order on ties
+    let mut ranking = (0..standings.len())
+        .filter(|x| !byes.contains(x))
+        .collect_vec();
+    ranking.sort_by(|&a, &b| standings[b].win_rate().total_cmp(&standings[a].win_rate()));
+
+    let mut tables = vec![];
+    while !ranking.is_empty() {
+        let first = ranking.remove(0);
+        // seat the next best ranked strategies forming a new table, if there is one
+        let candidates = ranking
+            .iter()
+            .copied()
+            .combinations(n_players - 1)
+            .map(|others| {
+                let mut table = vec![first];
+                table.extend(others);
+                table.sort();
+                table
+            });
+        let mut table = None;
+        for candidate in candidates {
+            let is_new = !played.contains(&candidate);
+            if table.is_none() || is_new {
+                table = Some(candidate);
+            }
+            if is_new {
+                break;
+            }
+        }
+        let table = table.expect("enough strategies");
+        ranking.retain(|x| !table.contains(x));
+        tables.push(table);
+    }
+    tables
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*; // bring private functions into scope
+    use rstest::rstest;
+
+    fn specs(names: &[&str]) -> Vec<String> {
+        names.iter().map(|x| x.to_string()).collect()
+    }
+
+    #[test]
+    fn test_round_robin_plays_every_seat_order() {
+        let tournament = Tournament::new(
+            specs(&["highest-points", "save-wilds", "dump-actions"]),
+            Rules::official(),
+        )
+        .n_games(3)
+        .seed(42);
+        let standings = tournament.run().unwrap();
+
+        // 3 tables with 2 seat orders of 3 games each
+        assert!(standings.iter().all(|x| x.n_games == 2 * 2 * 3));
+        assert_eq!(standings.iter().map(|x| x.n_wins).sum::<usize>(), 3 * 2 * 3);
+        assert!(standings
+            .windows(2)
+            .all(|x| x[0].win_rate() >= x[1].win_rate()));
+        assert_eq!(tournament.run().unwrap(), standings);
+    }
+
+    #[test]
+    fn test_run_rated_updates_ratings() {
+        let names = specs(&["highest-points", "save-wilds", "dump-actions"]);
+        let mut ratings = Ratings::default();
+        let standings = Tournament::new(names.clone(), Rules::official())
+            .n_players(3)
+            .n_games(2)
+            .seed(42)
+            .run_rated(&mut ratings)
+            .unwrap();
+
+        for standing in &standings {
+            assert_eq!(ratings.get(&standing.name).n_games, standing.n_games);
+        }
+        let total: f64 = names.iter().map(|x| ratings.get(x).rating).sum();
+        assert!((total - 3.0 * 1500.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_swiss_plays_rounds() {
+        let names = [
+            "random",
+            "save-wilds",
+            "dump-actions",
+            "highest-points",
+            "most-held-color",
+        ];
+        let standings = Tournament::new(specs(&names), Rules::official())
+            .n_games(2)
+            .seed(42)
+            .pairing(Pairing::Swiss { rounds: 3 })
+            .run()
+            .unwrap();
+
+        // one strategy sits out each round
+        let n_games: usize = standings.iter().map(|x| x.n_games).sum();
+        assert_eq!(n_games, 3 * 2 * 2 * 2 * 2);
+        assert!(standings
+            .iter()
+            .all(|x| x.n_games > 0 && x.n_games <= 3 * 2 * 2));
+    }
+
+    #[test]
+    fn test_swiss_tables_avoid_played_tables() {
+        let mut standings = specs(&["a", "b", "c", "d"])
+            .iter()
+            .map(|x| Standing::new(x))
+            .collect_vec();
+        let played = HashSet::new();
+        assert_eq!(
+            swiss_tables(&standings, 2, &played),
+            vec![vec![0, 1], vec![2, 3]]
+        );
+
+        standings[0].n_games = 2;
+        standings[0].n_wins = 2;
+        standings[2].n_games = 2;
+        standings[2].n_wins = 2;
+        let played = HashSet::from([vec![0, 2]]);
+        assert_eq!(
+            swiss_tables(&standings, 2, &played),
+            vec![vec![0, 1], vec![2, 3]]
+        );
+    }
+
+    #[rstest]
+    #[case::unknown_strategy(specs(&["random", "best"]), 2, "unknown strategy: best")]
+    #[case::duplicate_strategy(specs(&["random", "save-wilds", "random"]), 2, "duplicate strategy: random")]
+    #[case::too_many_players(specs(&["random", "random"]), 3, "cannot seat 3 players with 2 strategies")]
+    #[case::too_few_cards(specs(&["random"; 16]), 16, "invalid rules: deck of 108 cards cannot deal 7 cards to 16 players and a top card")]
+    fn test_tournament_errors(
+        #[case] specs: Vec<String>,
+        #[case] n_players: usize,
+        #[case] error: &str,
+    ) {
+        let result = Tournament::new(specs, Rules::official())
+            .n_players(n_players)
+            .run();
+        assert_eq!(result.unwrap_err(), error);
+    }
+
+    #[rstest]
+    #[case(0, 0, (0.0, 1.0))]
+    #[case(10, 5, (0.237, 0.763))]
+    #[case(100, 100, (0.963, 1.0))]
+    fn test_confidence_interval(
+        #[case] n_games: usize,
+        #[case] n_wins: usize,
+        #[case] expected: (f64, f64),
+    ) {
+        let standing = Standing {
+            n_games,
+            n_wins,
+            ..Standing::new("random")
+        };
+        let (low, high) = standing.confidence_interval();
+        assert!((low - expected.0).abs() < 1e-3, "{}", low);
+        assert!((high - expected.1).abs() < 1e-3, "{}", high);
+    }
+
+    #[test]
+    fn test_fmt_standings() {
+        let standing = Standing {
+            n_games: 10,
+            n_wins: 5,
+            points: 250,
+            ..Standing::new("random")
+        };
+        let table = fmt_standings(&[standing]);
+        let lines: Vec<_> = table.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[1].contains("50.0%"));
+        assert!(lines[1].contains("[23.7%, 76.3%]"));
+        assert!(lines[1].ends_with("25.0"));
+    }
+}
@@ -0,0 +1,196 @@
+use crate::server::{Lobby, ServerMessage, Session};
+use std::io::{self, BufRead, BufReader, ErrorKind, Read, Write};
+use std::net::{Shutdown, TcpStream};
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use tungstenite::{Message, WebSocket};
+
+const PAGE: &str = include_str!("../static/index.html"); // bundled browser client
+const POLL_INTERVAL: Duration = Duration::from_millis(20); // time between checking messages
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5); // time to receive request head
+const MAX_HEAD_SIZE: usize = 8192;
+
+/// Serve the browser client or exchange messages over a WebSocket with the client
+/// connected by `stream`, depending on its request.
+pub(crate) fn handle_connection(lobby: Arc<Lobby>, stream: TcpStream) {
+    match is_upgrade(&stream) {
+        Ok(true) => {
+            if let Ok(socket) = tungstenite::accept(stream) {
+                exchange_messages(lobby, socket);
+            }
+        }
+        Ok(false) => {
+            let _ = serve_page(stream);
+        }
+        Err(_) => (),
+    }
+}
+
+/// Check if the request received from `stream` asks for a WebSocket, without consuming
+/// it.
+fn is_upgrade(stream: &TcpStream) -> io::Result<bool> {
+    let mut buffer = [0; MAX_HEAD_SIZE];
+    let deadline = Instant::now() + REQUEST_TIMEOUT;
+    loop {
+        let n = stream.peek(&mut buffer)?;
+        let head = String::from_utf8_lossy(&buffer[..n]).to_lowercase();
+        if head.contains("\r\n\r\n") || n == buffer.len() {
+            return Ok(head.contains("upgrade: websocket"));
+        }
+        if n == 0 || Instant::now() > deadline {
+            return Err(io::Error::from(ErrorKind::UnexpectedEof));
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Answer HTTP request received from `stream` with the browser client, or with an
+/// error if its head is larger than [`MAX_HEAD_SIZE`].
+fn serve_page(mut stream: TcpStream) -> io::Result<()> {
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?).take(MAX_HEAD_SIZE as u64);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // skip headers, where the head ends with an empty line
+    let mut line = request_line.clone();
+    while line.ends_with('\n') && line.len() > 2 {
+        line.clear();
+        reader.read_line(&mut line)?;
+    }
+
+    let path = request_line.split_whitespace().nth(1).unwrap_or_default();
+    let (status, body) = match path {
+        _ if !line.ends_with('\n') => ("431 Request Header Fields Too Large", "Too large"),
+        "/" | "/index.html" => ("200 OK", PAGE),
+        _ => ("404 Not Found", "Not found"),
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )?;
+    stream.flush()?;
+
+    // drain the rest of the request before closing, so that the client is not reset
+    // before reading the response
+    stream.shutdown(Shutdown::Write)?;
+    io::copy(
+        &mut reader.into_inner().take(MAX_HEAD_SIZE as u64),
+        &mut io::sink(),
+    )?;
+    Ok(())
+}
+
+/// Exchange messages of the server protocol with the client connected by `socket`,
+/// sending each message as a text frame, until it disconnects.
+fn exchange_messages(lobby: Arc<Lobby>, mut socket: WebSocket<TcpStream>) {
+    // wait for frames only briefly, so that messages for the client are sent in time
+    if socket
+        .get_ref()
+        .set_read_timeout(Some(POLL_INTERVAL))
+        .is_err()
+    {
+        return;
+    }
+    let (outbox, messages) = mpsc::channel::<ServerMessage>();
+    let mut session = Session::new(lobby, outbox);
+    loop {
+        match socket.read() {
+            Ok(Message::Text(text)) => session.receive(text.as_str()),
+            Ok(Message::Close(_)) => return,
+            Ok(_) => (),
+            Err(tungstenite::Error::Io(error))
+                if matches!(error.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {}
+            Err(_) => return,
+        }
+        for message in messages.try_iter() {
+            let text = serde_json::to_string(&message).expect("messages are serializable");
+            if socket.send(Message::text(text)).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*; // bring private functions into scope
+    use crate::{set_verbose, Rules, Server};
+    use serde_json::{json, Value};
+    use std::net::{SocketAddr, TcpListener};
+
+    fn send<S: io::Read + io::Write>(socket: &mut WebSocket<S>, message: Value) {
+        socket.send(Message::text(message.to_string())).unwrap();
+    }
+
+    fn start_server() -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let web = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = web.local_addr().unwrap();
+        let server = Server::new(2, Rules::official()).seed(42).web(web);
+        thread::spawn(move || {
+            set_verbose(false);
+            server.serve(listener)
+        });
+        addr
+    }
+
+    #[test]
+    fn test_web_serves_page() {
+        let addr = start_server();
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(stream, "GET / HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.ends_with(PAGE));
+    }
+
+    #[test]
+    fn test_web_rejects_large_head() {
+        let addr = start_server();
+        let mut stream = TcpStream::connect(addr).unwrap();
+        let header = "a".repeat(MAX_HEAD_SIZE);
+        write!(stream, "GET / HTTP/1.1\r\nX-Large: {}\r\n\r\n", header).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 431 Request Header Fields Too Large"));
+    }
+
+    #[test]
+    fn test_web_plays_game_over_websocket() {
+        let addr = start_server();
+        let (mut socket, _) = tungstenite::connect(format!("ws://{}/", addr)).unwrap();
+        send(&mut socket, json!({"type": "join", "name": "Ann"}));
+        send(&mut socket, json!({"type": "start"}));
+
+        // play first legal card until the game is over
+        let mut types = vec![];
+        loop {
+            let text = socket.read().unwrap().into_text().unwrap();
+            let message: Value = serde_json::from_str(text.as_str()).unwrap();
+            let kind = message["type"].as_str().unwrap().to_string();
+            match kind.as_str() {
+                "turn" => {
+                    let card = &message["legal"][0];
+                    send(
+                        &mut socket,
+                        json!({"type": "play", "id": message["id"], "card": card}),
+                    );
+                }
+                "choose_color" => send(
+                    &mut socket,
+                    json!({"type": "color", "id": message["id"], "color": "Red"}),
+                ),
+                "game_over" => break,
+                _ => (),
+            }
+            types.push(kind);
+        }
+        assert_eq!(&types[..3], ["seated", "players", "started"]);
+    }
+}
@@ -0,0 +1,234 @@
+use uno::{
+    Card, Cards, Color, Dealer, DrawMode, DrawSource, Event, Game, Launcher, Play, Rules, Stacking,
+    Strategy, Symbol,
+};
+
+// helper function for testing to generate cards
+fn generate_cards(values: Vec<(Symbol, Option<Color>)>) -> Cards {
+    values
+        .into_iter()
+        .map(|(symbol, color)| Card::new(symbol, color))
+        .collect()
+}
+
+// helper strategy for testing which always plays the first legal card as red
+struct FirstCardStrategy {}
+
+impl Strategy for FirstCardStrategy {
+    fn select_card(&self, legal_cards: Cards) -> Play {
+        let mut card = legal_cards[0];
+        if card.color.is_none() {
+            card.color = Some(Color::Red);
+        }
+        Some(card)
+    }
+}
+
+// helper function to create game where all players use the first card strategy
+fn create_game(dealer: Dealer, hands: Vec<Cards>, first: usize, rules: Rules) -> Game {
+    let n_players = hands.len();
+    let mut game = Game::from_state(dealer, hands, first, rules);
+    for index in 0..n_players {
+        game.set_strategy(index, Box::new(FirstCardStrategy {}));
+    }
+    game
+}
+
+#[test]
+fn test_last_card_wild_draw_4_wins() {
+    let deck = generate_cards(vec![(Symbol::Number(1), Some(Color::Green)); 10]);
+    let pile = generate_cards(vec![(Symbol::Number(5), Some(Color::Red))]);
+    let hands = vec![
+        generate_cards(vec![(Symbol::Number(3), Some(Color::Blue))]),
+        generate_cards(vec![(Symbol::WildDraw4, None)]),
+        generate_cards(vec![(Symbol::Skip, Some(Color::Red))]),
+    ];
+    let mut game = create_game(Dealer::from_cards(deck, pile), hands, 1, Rules::official());
+
+    let result = game.step().expect("game not over");
+    assert_eq!(result.winner, 1);
+    assert_eq!(result.points, 3 + 20);
+    assert_eq!(game.top_card().symbol, Symbol::WildDraw4);
+}
+
+#[test]
+fn test_stacked_draw_2s_hit_third_player() {
+    let deck = generate_cards(vec![(Symbol::Number(1), Some(Color::Green)); 10]);
+    let pile = generate_cards(vec![(Symbol::Number(5), Some(Color::Red))]);
+    let hands = vec![
+        generate_cards(vec![
+            (Symbol::Draw2, Some(Color::Red)),
+            (Symbol::Number(9), Some(Color::Blue)),
+        ]),
+        generate_cards(vec![
+            (Symbol::Draw2, Some(Color::Green)),
+            (Symbol::Number(9), Some(Color::Yellow)),
+        ]),
+        generate_cards(vec![(Symbol::Number(7), Some(Color::Blue)); 3]),
+    ];
+    let rules = Rules {
+        stacking: Stacking::SameSymbol,
+        ..Rules::official()
+    };
+    let mut game = create_game(Dealer::from_cards(deck.clone(), pile), hands, 0, rules);
+
+    // first player plays draw 2, second player stacks draw 2, third player draws 4
+    assert!(game.step().is_none());
+    assert!(game.step().is_none());
+    assert_eq!(game.top_card().symbol, Symbol::Draw2);
+    assert!(game.step().is_none());
+    assert_eq!(game.hand(2).len(), 3 + 4);
+
+    // first player continues, drawing and playing a green card
+    assert!(game.step().is_none());
+    assert_eq!(game.hand(0).len(), 1);
+    assert_eq!(game.top_card(), deck[0]);
+}
+
+#[test]
+fn test_no_playable_card_draws_one() {
+    let deck = generate_cards(vec![
+        (Symbol::Number(2), Some(Color::Yellow)),
+        (Symbol::Number(3), Some(Color::Yellow)),
+    ]);
+    let pile = generate_cards(vec![(Symbol::Number(5), Some(Color::Red))]);
+    let hands = vec![
+        generate_cards(vec![(Symbol::Number(9), Some(Color::Blue)); 2]),
+        generate_cards(vec![(Symbol::Number(9), Some(Color::Green)); 2]),
+    ];
+    let mut game = create_game(
+        Dealer::from_cards(deck.clone(), pile),
+        hands,
+        0,
+        Rules::official(),
+    );
+
+    assert!(game.step().is_none());
+    assert_eq!(game.hand(0).len(), 3);
+    assert_eq!(game.hand(0)[2], deck[0]);
+}
+
+#[test]
+fn test_draw_2_hits_launcher_twice() {
+    let deck = generate_cards(vec![(Symbol::Number(1), Some(Color::Green)); 10]);
+    let pile = generate_cards(vec![(Symbol::Number(5), Some(Color::Red))]);
+    let hands = vec![
+        generate_cards(vec![
+            (Symbol::Draw2, Some(Color::Red)),
+            (Symbol::Number(9), Some(Color::Blue)),
+        ]),
+        generate_cards(vec![(Symbol::Number(9), Some(Color::Yellow)); 2]),
+    ];
+    let rules = Rules {
+        launcher: Some(Launcher::new(vec![0, 0, 0, 1])),
+        ..Rules::attack()
+    };
+    let mut game = create_game(Dealer::from_cards(deck, pile), hands, 0, rules);
+
+    // each hit of the launcher fires three cards
+    assert!(game.step().is_none());
+    assert!(game.step().is_none());
+    assert_eq!(game.hand(1).len(), 2 + 2 * 3);
+}
+
+// helper draw source for testing which never fires any cards
+#[derive(Debug)]
+struct EmptyLauncher;
+
+impl DrawSource for EmptyLauncher {
+    fn draw(&self, _dealer: &mut Dealer, _n_draws: usize) -> Cards {
+        vec![]
+    }
+}
+
+#[test]
+fn test_launcher_firing_no_cards_ends_turn() {
+    let deck = generate_cards(vec![(Symbol::Number(1), Some(Color::Green)); 10]);
+    let pile = generate_cards(vec![(Symbol::Number(5), Some(Color::Red))]);
+    let hands = vec![
+        generate_cards(vec![(Symbol::Number(9), Some(Color::Yellow)); 2]),
+        generate_cards(vec![(Symbol::Number(9), Some(Color::Blue)); 2]),
+    ];
+    let rules = Rules {
+        draw_mode: DrawMode::UntilPlayable,
+        ..Rules::attack()
+    };
+    let mut game = create_game(Dealer::from_cards(deck, pile), hands, 0, rules);
+    game.set_draw_source(Box::new(EmptyLauncher));
+
+    // drawing until playable stops when the launcher fires nothing
+    assert!(game.step().is_none());
+    assert_eq!(game.hand(0).len(), 2);
+}
+
+#[test]
+fn test_discard_all_discards_color_from_hand() {
+    let deck = generate_cards(vec![(Symbol::Number(1), Some(Color::Green)); 10]);
+    let pile = generate_cards(vec![(Symbol::Number(5), Some(Color::Red))]);
+    let hands = vec![
+        generate_cards(vec![
+            (Symbol::DiscardAll, Some(Color::Red)),
+            (Symbol::Number(3), Some(Color::Red)),
+            (Symbol::Number(4), Some(Color::Blue)),
+            (Symbol::Skip, Some(Color::Red)),
+        ]),
+        generate_cards(vec![(Symbol::Number(9), Some(Color::Yellow)); 2]),
+    ];
+    let mut game = create_game(Dealer::from_cards(deck, pile), hands, 0, Rules::no_mercy());
+
+    assert!(game.step().is_none());
+    assert_eq!(
+        game.hand(0),
+        generate_cards(vec![(Symbol::Number(4), Some(Color::Blue))])
+    );
+    assert_eq!(game.top_card().symbol, Symbol::DiscardAll);
+
+    // each discarded card counts as played
+    let n_played = game
+        .events()
+        .iter()
+        .filter(|x| matches!(x, Event::CardPlayed { player: 0, .. }))
+        .count();
+    assert_eq!(n_played, 3);
+}
+
+#[test]
+fn test_wild_reverse_draw_4_hits_previous_player() {
+    let deck = generate_cards(vec![(Symbol::Number(1), Some(Color::Green)); 10]);
+    let pile = generate_cards(vec![(Symbol::Number(5), Some(Color::Red))]);
+    let hands = vec![
+        generate_cards(vec![(Symbol::Number(9), Some(Color::Blue)); 2]),
+        generate_cards(vec![
+            (Symbol::WildReverseDraw4, None),
+            (Symbol::Number(9), Some(Color::Blue)),
+        ]),
+        generate_cards(vec![(Symbol::Number(9), Some(Color::Yellow)); 2]),
+    ];
+    let mut game = create_game(Dealer::from_cards(deck, pile), hands, 1, Rules::no_mercy());
+
+    assert!(game.step().is_none());
+    assert!(game.step().is_none());
+    assert_eq!(game.hand(0).len(), 2 + 4);
+    assert_eq!(game.hand(2).len(), 2);
+}
+
+#[test]
+fn test_player_reaching_mercy_limit_is_eliminated() {
+    let deck = generate_cards(vec![(Symbol::Number(1), Some(Color::Green)); 20]);
+    let pile = generate_cards(vec![(Symbol::Number(5), Some(Color::Red))]);
+    let hands = vec![
+        generate_cards(vec![
+            (Symbol::WildDraw10, None),
+            (Symbol::Number(9), Some(Color::Blue)),
+        ]),
+        generate_cards(vec![(Symbol::Number(9), Some(Color::Yellow)); 16]),
+    ];
+    let mut game = create_game(Dealer::from_cards(deck, pile), hands, 0, Rules::no_mercy());
+
+    // second player draws 10 cards, reaching 26 cards, and the first player is left
+    assert!(game.step().is_none());
+    let result = game.step().expect("game not over");
+    assert_eq!(result.winner, 0);
+    assert_eq!(result.placements, vec![0, 1]);
+    assert!(game.hand(1).is_empty());
+}